@@ -0,0 +1,72 @@
+use crate::{RwLock, RwLockReadInitGuard, SetTag};
+use futures::{Future, IntoFuture};
+use std::ops::Deref;
+
+/// A boxed `Future` returned by the `AsyncCache` operations.
+pub type CacheFuture<T, E> = Box<dyn Future<Item = T, Error = E> + Send>;
+
+/// A minimal asynchronous cache interface.
+///
+/// Application code can be written against this trait and be generic over the
+/// storage of the cached values.
+pub trait AsyncCache {
+    type Key;
+    type Value;
+    type Guard: Deref<Target = Self::Value>;
+
+    /// Returns the cached value, if any.
+    fn get(&self, key: &Self::Key) -> CacheFuture<Option<Self::Guard>, ()>;
+
+    /// Inserts or replaces the cached value.
+    fn insert(&self, key: Self::Key, value: Self::Value) -> CacheFuture<(), ()>;
+
+    /// Removes the cached value, forcing the next `get_or_compute` to compute it.
+    fn invalidate(&self, key: &Self::Key) -> CacheFuture<(), ()>;
+
+    /// Returns the cached value, computing it with `compute` if it is missing.
+    fn get_or_compute<F, FUT>(
+        &self,
+        key: Self::Key,
+        compute: F,
+    ) -> CacheFuture<Self::Guard, FUT::Error>
+    where
+        F: Fn() -> FUT + Send + 'static,
+        FUT: IntoFuture<Item = Self::Value> + 'static,
+        FUT::Future: Send;
+}
+
+impl<T> AsyncCache for RwLock<Option<T>>
+where
+    T: SetTag + Send + Sync + 'static,
+{
+    type Key = ();
+    type Value = T;
+    type Guard = RwLockReadInitGuard<T>;
+
+    fn get(&self, _: &()) -> CacheFuture<Option<Self::Guard>, ()> {
+        Box::new(self.read().map(|guard| {
+            if guard.is_some() {
+                Some(RwLockReadInitGuard(guard))
+            } else {
+                None
+            }
+        }))
+    }
+
+    fn insert(&self, _: (), value: T) -> CacheFuture<(), ()> {
+        Box::new(self.write().map(move |mut guard| *guard = Some(value)))
+    }
+
+    fn invalidate(&self, _: &()) -> CacheFuture<(), ()> {
+        Box::new(self.write().map(|mut guard| *guard = None))
+    }
+
+    fn get_or_compute<F, FUT>(&self, _: (), compute: F) -> CacheFuture<Self::Guard, FUT::Error>
+    where
+        F: Fn() -> FUT + Send + 'static,
+        FUT: IntoFuture<Item = T> + 'static,
+        FUT::Future: Send,
+    {
+        Box::new(self.read_or_init(compute))
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::SystemTime;
+use version_tag::VersionTag;
+
+/// A write committed on a `RwLock`, as delivered by the notification APIs.
+///
+/// The same event is recorded in the audit trail (see `RwLock::history`) and passed to
+/// the observers (see `TagObserver::on_change`).
+#[derive(Clone, Debug)]
+pub struct ChangeEvent {
+    /// The tag committed by the write.
+    pub tag: VersionTag,
+
+    /// When the write was committed.
+    pub at: SystemTime,
+
+    /// The actor given to the write guard, if any.
+    pub actor: Option<String>,
+
+    /// The label given to the write guard, if any.
+    pub label: Option<String>,
+
+    /// The reason given to the write guard, if any.
+    pub reason: Option<&'static str>,
+}
@@ -0,0 +1,55 @@
+use crate::timer::Timer;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, OnceLock};
+use std::time::{Instant, SystemTime};
+
+/// A source of time for the time-based features of the crate.
+///
+/// Locks use the `SystemClock` by default; tests can provide a mock clock to verify
+/// coalescing and expiration deterministically. See `RwLock::with_clock`.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    /// Returns the wall-clock time, used to timestamp the writes in the audit trail.
+    fn system_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    /// Returns a random number in `(0, 1]`, drawing the early refreshes of
+    /// `RwLock::read_or_refresh_early`.
+    ///
+    /// The default implementation takes the random keys of the std `RandomState`, which
+    /// are good enough for jitter and avoid depending on a random number crate.
+    fn random(&self) -> f64 {
+        let bits = RandomState::new().build_hasher().finish() >> 11;
+        (bits + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Calls `f` once the clock reaches `at`.
+    ///
+    /// The default implementation runs `f` on a single timer thread shared by the whole
+    /// process and started on the first call, so it works without any particular runtime.
+    fn schedule(&self, at: Instant, f: Box<dyn FnOnce() + Send>) {
+        let delay = at.saturating_duration_since(self.now());
+        Timer::global().schedule(Instant::now() + delay, f);
+    }
+}
+
+/// The `Clock` reading the system monotonic time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// The handle on the system clock shared by all the locks not given another clock.
+    pub(crate) fn shared() -> Arc<dyn Clock> {
+        static CLOCK: OnceLock<Arc<dyn Clock>> = OnceLock::new();
+        CLOCK.get_or_init(|| Arc::new(SystemClock)).clone()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
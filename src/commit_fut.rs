@@ -0,0 +1,46 @@
+use crate::{RwLockWriteGuard, SetTag};
+use futures::{Async, Future, Poll};
+use version_tag::VersionTag;
+
+/// A boxed `Future` returned by the finalizers registered with `RwLock::on_commit`.
+pub type FinalizeFuture = Box<dyn Future<Item = (), Error = ()> + Send>;
+
+pub(crate) type Finalizer<T> = Box<dyn FnMut(&T, VersionTag) -> FinalizeFuture + Send>;
+
+/// A `Future` running the finalizers of a write, then committing it even if one fails.
+///
+/// See `RwLockWriteGuard::commit`.
+pub struct CommitFut<T: ?Sized + SetTag> {
+    pub(crate) guard: Option<RwLockWriteGuard<T>>,
+    pub(crate) pending: Vec<FinalizeFuture>,
+}
+
+impl<T: ?Sized + SetTag> Future for CommitFut<T> {
+    type Item = VersionTag;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let mut i = 0;
+
+        while i < self.pending.len() {
+            match self.pending[i].poll() {
+                Ok(Async::Ready(())) => {
+                    drop(self.pending.swap_remove(i));
+                }
+                Ok(Async::NotReady) => i += 1,
+                Err(()) => {
+                    self.pending.clear();
+                    self.guard.take();
+                    return Err(());
+                }
+            }
+        }
+
+        if !self.pending.is_empty() {
+            return Ok(Async::NotReady);
+        }
+
+        let guard = self.guard.take().expect("Cannot poll twice");
+        Ok(Async::Ready(guard.finish()))
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{RwLock, RwLockWriteFut, TagStream, Tagged};
+use futures::{Async, Future, IntoFuture, Poll};
+use futures_locks::RwLockReadFut;
+use std::mem::replace;
+use version_tag::VersionTag;
+
+/// A hot-reloadable configuration, reloaded from any future resolving to a new one.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::ConfigLock;
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let config = ConfigLock::new(10);
+/// block_on_all(config.reload_from(Ok::<_, ()>(12))).unwrap();
+///
+/// assert_eq!(12, block_on_all(config.current().map(|c| **c)).unwrap());
+/// ```
+pub struct ConfigLock<T>(RwLock<Tagged<T>>);
+
+impl<T> ConfigLock<T> {
+    pub fn new(value: T) -> Self {
+        Self(RwLock::new_tagged(value))
+    }
+
+    /// Acquire the current configuration in read-only.
+    pub fn current(&self) -> RwLockReadFut<Tagged<T>> {
+        self.0.read()
+    }
+
+    /// Replaces the configuration with the one resolved by `source`.
+    ///
+    /// The returned `Future` resolves to the tag of the new configuration.
+    pub fn reload_from<S>(&self, source: S) -> ReloadFut<T, S>
+    where
+        S: IntoFuture<Item = T>,
+    {
+        ReloadFut {
+            lock: self.0.clone(),
+            state: ReloadState::Load(source.into_future()),
+        }
+    }
+
+    /// Returns a `Stream` of the tags of the reloaded configurations.
+    pub fn changed(&self) -> TagStream {
+        self.0.subscribe()
+    }
+
+    /// Returns the underlying lock.
+    pub fn lock(&self) -> &RwLock<Tagged<T>> {
+        &self.0
+    }
+}
+
+impl<T> Clone for ConfigLock<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// A `Future` reloading a `ConfigLock`.
+///
+/// See `ConfigLock::reload_from`.
+pub struct ReloadFut<T, S: IntoFuture<Item = T>> {
+    lock: RwLock<Tagged<T>>,
+    state: ReloadState<T, S::Future>,
+}
+
+impl<T, S> Future for ReloadFut<T, S>
+where
+    S: IntoFuture<Item = T>,
+{
+    type Item = VersionTag;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            match replace(&mut self.state, ReloadState::Done) {
+                ReloadState::Done => panic!("Cannot poll twice"),
+                ReloadState::Load(mut f) => match f.poll()? {
+                    Async::Ready(value) => {
+                        self.state = ReloadState::Write(self.lock.write(), value);
+                    }
+                    Async::NotReady => {
+                        self.state = ReloadState::Load(f);
+                        return Ok(Async::NotReady);
+                    }
+                },
+                ReloadState::Write(mut f, value) => match f.poll() {
+                    Ok(Async::Ready(mut guard)) => {
+                        **guard = value;
+                        return Ok(Async::Ready(guard.new_tag()));
+                    }
+                    Ok(Async::NotReady) => {
+                        self.state = ReloadState::Write(f, value);
+                        return Ok(Async::NotReady);
+                    }
+                    Err(_) => unreachable!("Lock error"),
+                },
+            }
+        }
+    }
+}
+
+enum ReloadState<T, F> {
+    Done,
+    Load(F),
+    Write(RwLockWriteFut<Tagged<T>>, T),
+}
@@ -0,0 +1,70 @@
+use crate::{Clock, TagStream};
+use futures::{task, Async, Poll, Stream};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use version_tag::VersionTag;
+
+/// A `Stream` yielding the first tag right away, then at most the latest tag per window.
+///
+/// See `TagStream::debounce`.
+pub struct Debounce {
+    clock: Arc<dyn Clock>,
+    done: bool,
+    next: Option<Instant>,
+    pending: Option<VersionTag>,
+    scheduled: Option<Instant>,
+    stream: TagStream,
+    window: Duration,
+}
+
+impl Debounce {
+    pub(crate) fn new(stream: TagStream, clock: Arc<dyn Clock>, window: Duration) -> Self {
+        Self {
+            clock,
+            done: false,
+            next: None,
+            pending: None,
+            scheduled: None,
+            stream,
+            window,
+        }
+    }
+}
+
+impl Stream for Debounce {
+    type Item = VersionTag;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        while !self.done {
+            match self.stream.poll()? {
+                Async::Ready(Some(tag)) => self.pending = Some(tag),
+                Async::Ready(None) => self.done = true,
+                Async::NotReady => break,
+            }
+        }
+
+        if self.pending.is_some() {
+            let now = self.clock.now();
+
+            match self.next {
+                Some(next) if now < next => {
+                    if self.scheduled != Some(next) {
+                        let task = task::current();
+
+                        self.scheduled = Some(next);
+                        self.clock.schedule(next, Box::new(move || task.notify()));
+                    }
+                }
+                _ => {
+                    self.next = Some(now + self.window);
+                    return Ok(Async::Ready(self.pending.take()));
+                }
+            }
+        } else if self.done {
+            return Ok(Async::Ready(None));
+        }
+
+        Ok(Async::NotReady)
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{Clock, LockId};
+use futures::sync::mpsc::UnboundedReceiver;
+use futures::{Poll, Stream};
+use std::time::{Duration, Instant};
+use version_tag::VersionTag;
+
+/// A structured event about the activity of a `RwLock`.
+///
+/// See `RwLock::diagnostics`.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    /// What happened.
+    pub event: DiagnosticEvent,
+
+    /// The identifier of the lock.
+    pub id: LockId,
+
+    /// The name of the lock, if any.
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiagnosticEvent {
+    /// A write guard was acquired.
+    Acquired,
+
+    /// A write guard was released after being held for `held`. The `tag` is `None` when
+    /// the write was discarded without committing.
+    Released {
+        held: Duration,
+        tag: Option<VersionTag>,
+    },
+
+    /// A write guard was held longer than the threshold set with `RwLock::on_long_hold`.
+    LongHold { held: Duration, tag: VersionTag },
+
+    /// A write guard was released while its thread was panicking; the value may have
+    /// been left half updated, so the write was not committed.
+    Poisoned,
+
+    /// A write was rejected by `RwLock::queue_write` because `max` writers were waiting.
+    QueueFull { max: usize },
+
+    /// A value was initialized by `read_or_init` or `write_or_init`, or one of their
+    /// refresh variants. `waited` is the time spent waiting for the lock before the
+    /// initializer started, and `init` the time spent running the initializer.
+    Initialized { init: Duration, waited: Duration },
+
+    /// A `read_or_init` or `write_or_init`, or one of their refresh variants, waited
+    /// `waited` for the lock and found the value initialized by another caller.
+    InitializedByOther { waited: Duration },
+}
+
+/// Splits the duration of an initializing read or write between the lock waits and the
+/// initializer.
+///
+/// The timer starts on the first poll of the future rather than at its creation, so a
+/// future created ahead of time does not count as waiting.
+#[derive(Default)]
+pub(crate) struct InitTimer {
+    contended: bool,
+    started: Option<Instant>,
+    waited: Duration,
+}
+
+impl InitTimer {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts the timer, once. `contended` tells if another initializer was running.
+    pub(crate) fn start(&mut self, clock: &dyn Clock, contended: bool) {
+        if self.started.is_none() {
+            self.contended = contended;
+            self.started = Some(clock.now());
+        }
+    }
+
+    /// Records that the value was found missing, so another caller initialized it if it
+    /// is found ready later.
+    pub(crate) fn contend(&mut self) {
+        self.contended = true;
+    }
+
+    /// Marks the start of the initializer.
+    pub(crate) fn init_started(&mut self, now: Instant) {
+        self.contended = false;
+        self.waited = self.elapsed(now);
+        self.started = Some(now);
+    }
+
+    /// Marks the end of the initializer and returns how long it ran.
+    pub(crate) fn init_done(&self, now: Instant) -> Duration {
+        self.elapsed(now)
+    }
+
+    /// Returns the event to report, once, when the value is found ready after another
+    /// initializer was running or the value was found missing.
+    pub(crate) fn by_other(&mut self, now: Instant) -> Option<DiagnosticEvent> {
+        if !self.contended {
+            return None;
+        }
+
+        self.contended = false;
+        Some(DiagnosticEvent::InitializedByOther {
+            waited: self.elapsed(now),
+        })
+    }
+
+    fn elapsed(&self, now: Instant) -> Duration {
+        self.started
+            .map_or(Duration::default(), |started| now.duration_since(started))
+    }
+
+    /// Returns the event to report for an initializer that ran for `init`.
+    pub(crate) fn event(&self, init: Duration) -> DiagnosticEvent {
+        DiagnosticEvent::Initialized {
+            init,
+            waited: self.waited,
+        }
+    }
+}
+
+/// A `Stream` of the diagnostic events of a `RwLock`.
+///
+/// See `RwLock::diagnostics`.
+pub struct DiagnosticStream(pub(crate) UnboundedReceiver<Diagnostic>);
+
+impl Stream for DiagnosticStream {
+    type Item = Diagnostic;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.0.poll()
+    }
+}
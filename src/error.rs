@@ -0,0 +1,33 @@
+use crate::LockId;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// The error returned by `RwLock::queue_write` when too many writers are already
+/// waiting on the lock.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueueFull {
+    /// The identifier of the lock.
+    pub id: LockId,
+
+    /// The maximum number of queued writers configured on the lock.
+    pub max: usize,
+
+    /// The name of the lock, if any.
+    pub name: Option<String>,
+}
+
+impl Display for QueueFull {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_lock(f, self.id, &self.name)?;
+        write!(f, ": write queue full ({} waiters)", self.max)
+    }
+}
+
+fn write_lock(f: &mut Formatter, id: LockId, name: &Option<String>) -> fmt::Result {
+    match name {
+        Some(name) => write!(f, "{} ({})", name, id),
+        None => write!(f, "{}", id),
+    }
+}
+
+impl Error for QueueFull {}
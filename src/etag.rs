@@ -0,0 +1,42 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::OnceLock;
+use version_tag::VersionTag;
+
+/// Returns the HTTP ETag (quoted) representing a `VersionTag`.
+///
+/// The tag is hashed through its `Debug` representation with a nonce drawn per process,
+/// so a restarted server never matches the ETags of the previous one.
+///
+/// # Example
+///
+/// ```
+/// use futures_tag_locks::{etag, if_none_match, Tagged};
+///
+/// let value = Tagged::new(10);
+/// let header = etag(value.tag());
+///
+/// assert!(if_none_match(&header, value.tag()));
+/// ```
+pub fn etag(tag: VersionTag) -> String {
+    format!("\"{:016x}\"", nonce().hash_one(format!("{:?}", tag)))
+}
+
+/// The hashing keys of the ETags, random for each process.
+fn nonce() -> &'static RandomState {
+    static NONCE: OnceLock<RandomState> = OnceLock::new();
+    NONCE.get_or_init(RandomState::new)
+}
+
+/// Returns true if an `If-None-Match` header value matches the tag, in which case the
+/// handler can answer `304 Not Modified`.
+///
+/// Handles `*`, lists of ETags and weak ETags (`W/"..."`).
+pub fn if_none_match(header: &str, tag: VersionTag) -> bool {
+    let expected = etag(tag);
+
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|v| v == "*" || v.strip_prefix("W/").unwrap_or(v) == expected)
+}
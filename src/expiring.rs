@@ -0,0 +1,126 @@
+use crate::{Clock, SetTag, SystemClock};
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
+use version_tag::VersionTag;
+
+/// A wrapper class that handle the version tag, the value and an expiration deadline.
+///
+/// Used with `RwLock::read_or_refresh` and `RwLock::write_or_refresh`, an expired value
+/// is re-initialized as if it was missing. Those methods check the expiration against
+/// the `Clock` of the lock.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{Expiring, RwLock};
+/// use std::time::Duration;
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let lock = RwLock::new(None);
+/// let ttl = Duration::from_secs(60);
+///
+/// let init = move || Ok::<_, ()>(Expiring::new(10, ttl));
+/// let value = block_on_all(lock.read_or_refresh(init).map(|v| **v)).unwrap();
+///
+/// assert_eq!(10, value);
+/// ```
+#[derive(Clone)]
+pub struct Expiring<T: ?Sized> {
+    deadline: Instant,
+    delta: Duration,
+    tag: VersionTag,
+    ttl: Option<Duration>,
+    value: T,
+}
+
+impl<T> Expiring<T> {
+    /// Creates a value expiring after `ttl`.
+    ///
+    /// The `ttl` is counted from the system clock, then from the `Clock` of the lock
+    /// once the value is stored by an initializing read or write.
+    pub fn new(value: T, ttl: Duration) -> Self {
+        Self::with_clock(value, ttl, &SystemClock)
+    }
+
+    /// Creates a value expiring after `ttl`, counted from the time of `clock`.
+    pub fn with_clock(value: T, ttl: Duration, clock: &dyn Clock) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..Self::with_deadline(value, clock.now() + ttl)
+        }
+    }
+
+    /// Creates a value expiring at `deadline`.
+    pub fn with_deadline(value: T, deadline: Instant) -> Self {
+        Self {
+            deadline,
+            delta: Duration::default(),
+            tag: VersionTag::new(),
+            ttl: None,
+            value,
+        }
+    }
+}
+
+impl<T: ?Sized> Expiring<T> {
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns true if the value is expired at the current time of `clock`.
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        self.is_expired_at(clock.now())
+    }
+
+    /// Returns true if the value is expired at the instant `now`.
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        now >= self.deadline
+    }
+
+    /// Returns how long the initializer that produced this value ran, as measured by
+    /// the lock. Zero until the value goes through an initializing read or write.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Records how long the initializer ran and counts the `ttl`, if any, from `now`.
+    pub(crate) fn initialized(&mut self, delta: Duration, now: Instant) {
+        self.delta = delta;
+
+        if let Some(ttl) = self.ttl {
+            self.deadline = now + ttl;
+        }
+    }
+
+    /// Returns true if the value is expired or wins the early refresh draw of XFetch,
+    /// `now - delta * beta * ln(random)` reaching the deadline.
+    pub fn should_refresh_early(&self, clock: &dyn Clock, beta: f64) -> bool {
+        let gap = self.delta.as_secs_f64() * beta * -clock.random().ln();
+        clock.now() + Duration::from_secs_f64(gap) >= self.deadline
+    }
+
+    pub fn tag(&self) -> VersionTag {
+        self.tag
+    }
+}
+
+impl<T: ?Sized> Deref for Expiring<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: ?Sized> DerefMut for Expiring<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: ?Sized> SetTag for Expiring<T> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        self.tag = tag;
+    }
+}
@@ -0,0 +1,71 @@
+use crate::SetTag;
+use std::ops::{Deref, DerefMut};
+use version_tag::VersionTag;
+
+/// A wrapper applying the tag to its value with a closure.
+///
+/// Lets foreign types, on which `SetTag` cannot be implemented, carry the tag in one of
+/// their own fields.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{FnTagged, RwLock, VersionTag};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// struct Document {
+///     text: String,
+///     version: Option<VersionTag>,
+/// }
+///
+/// let doc = Document { text: String::new(), version: None };
+/// let lock = RwLock::new(FnTagged::new(doc, |d: &mut Document, tag| d.version = Some(tag)));
+///
+/// let tag = block_on_all(lock.write().map(|mut w| {
+///     w.text.push_str("hello");
+///     w.new_tag()
+/// })).unwrap();
+///
+/// assert_eq!(Some(tag), block_on_all(lock.read().map(|d| d.version)).unwrap());
+/// ```
+pub struct FnTagged<T, F> {
+    f: F,
+    value: T,
+}
+
+impl<T, F> FnTagged<T, F>
+where
+    F: FnMut(&mut T, VersionTag),
+{
+    pub fn new(value: T, f: F) -> Self {
+        Self { f, value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, F> Deref for FnTagged<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, F> DerefMut for FnTagged<T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T, F> SetTag for FnTagged<T, F>
+where
+    F: FnMut(&mut T, VersionTag),
+{
+    fn set_tag(&mut self, tag: VersionTag) {
+        (self.f)(&mut self.value, tag);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::sync::Mutex;
+use crate::{TagObserver, TagStream, WeakRwLock};
+use futures::sync::mpsc::Sender;
+use futures::task::{self, Task};
+use futures::{Async, AsyncSink, Future, Poll, Sink, Stream};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use version_tag::VersionTag;
+
+/// What `ForwardTags` does with new tags when the channel is full.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FullPolicy {
+    /// Keep every tag until the channel has capacity. The writers never wait, so the
+    /// pending tags are buffered without limit while the receiver is not reading.
+    KeepAll,
+
+    /// Discard the oldest pending tags, only the newest one waits for capacity.
+    DropOldest,
+}
+
+/// A `Future` forwarding the tags of a `RwLock` into a channel, until either is dropped.
+///
+/// See `RwLock::forward_tags`.
+pub struct ForwardTags<T> {
+    closed: bool,
+    lock: WeakRwLock<T>,
+    policy: FullPolicy,
+    queue: Arc<TagQueue>,
+    sender: Sender<VersionTag>,
+    stream: TagStream,
+}
+
+impl<T> ForwardTags<T> {
+    pub(crate) fn new(
+        lock: WeakRwLock<T>,
+        queue: Arc<TagQueue>,
+        stream: TagStream,
+        sender: Sender<VersionTag>,
+        policy: FullPolicy,
+    ) -> Self {
+        Self {
+            closed: false,
+            lock,
+            policy,
+            queue,
+            sender,
+            stream,
+        }
+    }
+
+    fn finish(&self) -> Poll<(), ()> {
+        self.unobserve();
+        Ok(Async::Ready(()))
+    }
+
+    fn unobserve(&self) {
+        if let Some(lock) = self.lock.upgrade() {
+            let observer: Arc<dyn TagObserver> = self.queue.clone();
+            lock.unobserve(&observer);
+        }
+    }
+}
+
+/// Stops queuing the tags of the lock when the future is dropped before completing.
+impl<T> Drop for ForwardTags<T> {
+    fn drop(&mut self) {
+        self.unobserve();
+    }
+}
+
+impl<T> Future for ForwardTags<T> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        // the stream only signals the closing of the lock, tags come from the queue.
+        while !self.closed {
+            match self.stream.poll()? {
+                Async::Ready(Some(_)) => {}
+                Async::Ready(None) => self.closed = true,
+                Async::NotReady => break,
+            }
+        }
+
+        loop {
+            let tag = {
+                let mut state = self.queue.state.lock().expect("ForwardTags");
+                state.task = Some(task::current());
+
+                match state.tags.front() {
+                    Some(tag) => *tag,
+                    None => break,
+                }
+            };
+
+            match self.sender.start_send(tag) {
+                Ok(AsyncSink::Ready) => {
+                    self.queue
+                        .state
+                        .lock()
+                        .expect("ForwardTags")
+                        .tags
+                        .pop_front();
+                }
+                Ok(AsyncSink::NotReady(_)) => {
+                    if self.policy == FullPolicy::DropOldest {
+                        let tags = &mut self.queue.state.lock().expect("ForwardTags").tags;
+                        let len = tags.len();
+                        tags.drain(..len - 1);
+                    }
+                    break;
+                }
+                Err(_) => return self.finish(),
+            }
+        }
+
+        if self.sender.poll_complete().is_err() {
+            return self.finish();
+        }
+
+        if self.closed
+            && self
+                .queue
+                .state
+                .lock()
+                .expect("ForwardTags")
+                .tags
+                .is_empty()
+        {
+            return Ok(Async::Ready(()));
+        }
+
+        Ok(Async::NotReady)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TagQueue {
+    state: Mutex<TagQueueState>,
+}
+
+impl TagObserver for TagQueue {
+    fn on_tag(&self, tag: VersionTag) {
+        let mut state = self.state.lock().expect("TagQueue");
+        state.tags.push_back(tag);
+
+        if let Some(task) = state.task.take() {
+            task.notify();
+        }
+    }
+}
+
+#[derive(Default)]
+struct TagQueueState {
+    tags: VecDeque<VersionTag>,
+    task: Option<Task>,
+}
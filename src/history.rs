@@ -0,0 +1,41 @@
+use crate::ChangeEvent;
+use std::collections::VecDeque;
+
+/// A write recorded in the audit trail of a `RwLock`.
+///
+/// See `RwLock::keep_history`.
+pub type HistoryEntry = ChangeEvent;
+
+#[derive(Default)]
+pub(crate) struct History {
+    capacity: usize,
+    entries: VecDeque<HistoryEntry>,
+}
+
+impl History {
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.truncate();
+    }
+
+    pub(crate) fn entries(&self) -> Vec<HistoryEntry> {
+        self.entries.iter().cloned().collect()
+    }
+
+    pub(crate) fn last(&self) -> Option<HistoryEntry> {
+        self.entries.back().cloned()
+    }
+
+    pub(crate) fn push(&mut self, event: &ChangeEvent) {
+        if self.capacity > 0 {
+            self.entries.push_back(event.clone());
+            self.truncate();
+        }
+    }
+
+    fn truncate(&mut self) {
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+}
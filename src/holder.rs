@@ -0,0 +1,74 @@
+#[cfg(feature = "debug-tracking")]
+use crate::sync::Mutex;
+#[cfg(feature = "debug-tracking")]
+use std::backtrace::Backtrace;
+use std::panic::Location;
+#[cfg(feature = "debug-tracking")]
+use std::sync::Arc;
+use std::time::Instant;
+
+/// The holder of the write lock, captured when the `debug-tracking` feature is enabled.
+///
+/// See `RwLock::holder`.
+#[cfg(feature = "debug-tracking")]
+#[derive(Clone, Debug)]
+pub struct Holder {
+    /// When the write guard was acquired.
+    pub acquired: Instant,
+
+    /// The backtrace at the acquisition, captured according to `RUST_BACKTRACE`.
+    pub backtrace: Arc<Backtrace>,
+
+    /// Where the write was requested.
+    pub location: &'static Location<'static>,
+}
+
+/// The call site requesting a write, only recorded with the `debug-tracking` feature.
+#[derive(Clone, Copy)]
+pub(crate) struct CallSite {
+    #[cfg(feature = "debug-tracking")]
+    location: &'static Location<'static>,
+}
+
+impl CallSite {
+    #[track_caller]
+    pub(crate) fn caller() -> Self {
+        let _location = Location::caller();
+
+        Self {
+            #[cfg(feature = "debug-tracking")]
+            location: _location,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct HolderSlot {
+    #[cfg(feature = "debug-tracking")]
+    slot: Mutex<Option<Holder>>,
+}
+
+impl HolderSlot {
+    #[cfg(feature = "debug-tracking")]
+    pub(crate) fn get(&self) -> Option<Holder> {
+        self.slot.lock().expect("HolderSlot").clone()
+    }
+
+    pub(crate) fn acquire(&self, _site: CallSite, _acquired: Instant) {
+        #[cfg(feature = "debug-tracking")]
+        {
+            *self.slot.lock().expect("HolderSlot") = Some(Holder {
+                acquired: _acquired,
+                backtrace: Arc::new(Backtrace::capture()),
+                location: _site.location,
+            });
+        }
+    }
+
+    pub(crate) fn release(&self) {
+        #[cfg(feature = "debug-tracking")]
+        {
+            *self.slot.lock().expect("HolderSlot") = None;
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::sync::Mutex;
+use futures::task::{self, Task};
+use futures::{Async, Future, Poll};
+use std::sync::Arc;
+
+/// A `Future` resolving once no initializer of a `RwLock<Option<T>>` is running.
+///
+/// Unlike `read_or_init`, waiting on this future does not queue behind the write lock
+/// held by the initializer, so it can be combined with a fallback value while a slow
+/// first load is in progress.
+pub struct Initialized(Arc<InitGate>);
+
+impl Future for Initialized {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let mut state = self.0.state.lock().expect("Initialized");
+
+        if state.active == 0 {
+            return Ok(Async::Ready(()));
+        }
+
+        if !state.tasks.iter().any(Task::will_notify_current) {
+            state.tasks.push(task::current());
+        }
+
+        Ok(Async::NotReady)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct InitGate {
+    state: Mutex<GateState>,
+}
+
+impl InitGate {
+    pub(crate) fn enter(gate: &Arc<Self>) -> InitToken {
+        gate.state.lock().expect("InitGate").active += 1;
+        InitToken(gate.clone())
+    }
+
+    /// Enters the gate, unless an initializer is already running.
+    pub(crate) fn try_enter(gate: &Arc<Self>) -> Option<InitToken> {
+        let mut state = gate.state.lock().expect("InitGate");
+
+        if state.active > 0 {
+            return None;
+        }
+
+        state.active += 1;
+        Some(InitToken(gate.clone()))
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.state.lock().expect("InitGate").active > 0
+    }
+
+    pub(crate) fn wait(gate: &Arc<Self>) -> Initialized {
+        Initialized(gate.clone())
+    }
+}
+
+#[derive(Default)]
+struct GateState {
+    active: usize,
+    tasks: Vec<Task>,
+}
+
+/// Marks an initializer as running until dropped.
+pub(crate) struct InitToken(Arc<InitGate>);
+
+impl Drop for InitToken {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().expect("InitToken");
+
+        state.active -= 1;
+
+        if state.active == 0 {
+            state.tasks.drain(..).for_each(|t| t.notify());
+        }
+    }
+}
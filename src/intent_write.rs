@@ -0,0 +1,76 @@
+use crate::{RwLock, RwLockWriteFut, RwLockWriteGuard, SetTag};
+use futures::{try_ready, Async, Future, Poll};
+use futures_locks::{MutexFut, MutexGuard, RwLockReadFut};
+
+/// A `Future` acquiring the write intent of a `RwLock`.
+///
+/// See `RwLock::intent_write`.
+pub struct IntentWriteFut<T: ?Sized> {
+    pub(crate) fut: MutexFut<()>,
+    pub(crate) lock: RwLock<T>,
+}
+
+impl<T: ?Sized> Future for IntentWriteFut<T> {
+    type Item = RwLockIntentGuard<T>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let intent = try_ready!(self.fut.poll());
+
+        Ok(Async::Ready(RwLockIntentGuard {
+            intent,
+            lock: self.lock.clone(),
+        }))
+    }
+}
+
+/// A guard holding the write intent of a `RwLock`.
+///
+/// Only one task holds the intent at a time, while the readers keep going. The holder
+/// can read the value as often as needed and then `upgrade` to the write lock. The
+/// intent is released when the guard is dropped or when the upgrade completes.
+pub struct RwLockIntentGuard<T: ?Sized> {
+    intent: MutexGuard<()>,
+    lock: RwLock<T>,
+}
+
+impl<T: ?Sized> RwLockIntentGuard<T> {
+    /// Acquire the `RwLock` in read-only, while keeping the intent.
+    pub fn read(&self) -> RwLockReadFut<T> {
+        self.lock.read()
+    }
+
+    /// Exchanges the intent for the write lock.
+    ///
+    /// The intent is kept until the write guard is acquired, so no other intent holder
+    /// can slip in between.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn upgrade(self) -> UpgradeFut<T>
+    where
+        T: SetTag,
+    {
+        UpgradeFut {
+            fut: self.lock.write(),
+            intent: Some(self.intent),
+        }
+    }
+}
+
+/// A `Future` exchanging the write intent for the write lock.
+///
+/// See `RwLockIntentGuard::upgrade`.
+pub struct UpgradeFut<T: ?Sized + SetTag> {
+    fut: RwLockWriteFut<T>,
+    intent: Option<MutexGuard<()>>,
+}
+
+impl<T: ?Sized + SetTag> Future for UpgradeFut<T> {
+    type Item = RwLockWriteGuard<T>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let guard = try_ready!(self.fut.poll());
+        self.intent = None;
+        Ok(Async::Ready(guard))
+    }
+}
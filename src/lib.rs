@@ -1,10 +1,112 @@
+mod async_cache;
+mod change_event;
+mod clock;
+mod commit_fut;
+mod config_lock;
+mod debounce;
+mod diagnostics;
+mod error;
+mod etag;
+mod expiring;
+mod fn_tagged;
+mod forward_tags;
+mod history;
+mod holder;
+mod initializing;
+mod intent_write;
+mod lock_id;
+mod long_hold;
+mod macros;
+mod merge;
+#[cfg(feature = "watch")]
+mod mirror_to_watch;
+mod modify;
+mod patch;
+mod read_latest;
+mod read_validated;
 mod rw_lock;
+mod rw_lock_builder;
+mod rw_lock_pool;
+mod scope;
+mod send_sync;
 mod set_tag;
+mod singleflight;
+mod snapshot_receiver;
+mod static_tag_lock;
+mod swap_fut;
+mod sync;
+mod sync_tagged;
+mod tag_observer;
+mod tag_on_drop;
+mod tag_stream;
 mod tagged;
+mod tagged_atomic;
+mod tagged_hash_map;
+mod tagged_regions;
+mod tagged_seq;
+mod tagged_sink;
+mod tagged_slab;
+mod tagged_vec;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod timer;
 mod untagged;
+mod value_stream;
+mod weak_rw_lock;
+mod write_and_notify;
+mod write_if;
 
+pub use self::async_cache::*;
+pub use self::change_event::*;
+pub use self::clock::*;
+pub use self::commit_fut::{CommitFut, FinalizeFuture};
+pub use self::config_lock::*;
+pub use self::debounce::*;
+pub use self::diagnostics::*;
+pub use self::error::*;
+pub use self::etag::*;
+pub use self::expiring::*;
+pub use self::fn_tagged::*;
+pub use self::forward_tags::*;
+pub use self::history::*;
+#[cfg(feature = "debug-tracking")]
+pub use self::holder::Holder;
+pub use self::initializing::Initialized;
+pub use self::intent_write::*;
+pub use self::lock_id::*;
+pub use self::long_hold::*;
+pub use self::merge::*;
+#[cfg(feature = "watch")]
+pub use self::mirror_to_watch::*;
+pub use self::modify::*;
+pub use self::patch::*;
+pub use self::read_latest::*;
+pub use self::read_validated::*;
 pub use self::rw_lock::*;
+pub use self::rw_lock_builder::*;
+pub use self::rw_lock_pool::*;
+pub use self::scope::*;
 pub use self::set_tag::*;
+pub use self::singleflight::*;
+pub use self::snapshot_receiver::*;
+pub use self::static_tag_lock::*;
+pub use self::swap_fut::SwapFut;
+pub use self::sync_tagged::*;
+pub use self::tag_observer::*;
+pub use self::tag_on_drop::*;
+pub use self::tag_stream::*;
 pub use self::tagged::*;
+pub use self::tagged_atomic::*;
+pub use self::tagged_hash_map::*;
+pub use self::tagged_regions::*;
+pub use self::tagged_seq::*;
+pub use self::tagged_sink::*;
+pub use self::tagged_slab::*;
+pub use self::tagged_vec::*;
 pub use self::untagged::*;
+pub use self::value_stream::*;
+pub use self::weak_rw_lock::*;
+pub use self::write_and_notify::*;
+pub use self::write_if::*;
 pub use futures_locks::{RwLockReadFut, RwLockReadGuard};
+pub use version_tag::VersionTag;
@@ -0,0 +1,23 @@
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// A unique identifier of a `RwLock`, shared by all its clones.
+///
+/// The identifiers are ordered by creation, which gives a deterministic order to
+/// acquire several locks.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LockId(usize);
+
+impl LockId {
+    pub(crate) fn next() -> Self {
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Display for LockId {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "lock#{}", self.0)
+    }
+}
@@ -0,0 +1,27 @@
+use crate::LockId;
+use std::sync::Arc;
+use std::time::Duration;
+use version_tag::VersionTag;
+
+/// A report of a write guard held longer than the threshold of a lock.
+///
+/// See `RwLock::on_long_hold`.
+#[derive(Clone, Debug)]
+pub struct LongHold {
+    /// How long the write guard has been held.
+    pub held: Duration,
+
+    /// The identifier of the lock.
+    pub id: LockId,
+
+    /// The name of the lock, if any.
+    pub name: Option<String>,
+
+    /// The tag committed by the write.
+    pub tag: VersionTag,
+}
+
+pub(crate) struct LongHoldWatch {
+    pub(crate) report: Arc<dyn Fn(&LongHold) + Send + Sync>,
+    pub(crate) threshold: Duration,
+}
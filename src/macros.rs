@@ -0,0 +1,29 @@
+/// Creates a `RwLock<Tagged<T>>` protecting the given value, or declares a static one
+/// initialized on first use.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::tagged_rwlock;
+///
+/// tagged_rwlock! {
+///     static NAMES: Vec<&'static str> = vec!["a", "b"];
+/// }
+///
+/// let lock = tagged_rwlock!(vec![1, 2, 3]);
+///
+/// assert_eq!(2, NAMES.read().wait().unwrap().len());
+/// ```
+#[macro_export]
+macro_rules! tagged_rwlock {
+    ($($vis:vis static $name:ident: $ty:ty = $value:expr;)+) => {
+        $(
+            $vis static $name: ::std::sync::LazyLock<$crate::RwLock<$crate::Tagged<$ty>>> =
+                ::std::sync::LazyLock::new(|| $crate::RwLock::new_tagged($value));
+        )+
+    };
+    ($value:expr) => {
+        $crate::RwLock::new_tagged($value)
+    };
+}
@@ -0,0 +1,37 @@
+use crate::Tagged;
+
+/// A value merging the state of another replica into its own, CRDT-style.
+///
+/// See `RwLock::merge`.
+///
+/// # Example
+///
+/// ```
+/// use futures_tag_locks::{Merge, RwLock};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// struct MaxRegister(u64);
+///
+/// impl Merge for MaxRegister {
+///     fn merge(&mut self, other: Self) -> bool {
+///         let changed = other.0 > self.0;
+///         self.0 = self.0.max(other.0);
+///         changed
+///     }
+/// }
+///
+/// let lock = RwLock::new_tagged(MaxRegister(5));
+///
+/// assert!(block_on_all(lock.merge(MaxRegister(3))).unwrap().is_none());
+/// assert!(block_on_all(lock.merge(MaxRegister(8))).unwrap().is_some());
+/// ```
+pub trait Merge<Rhs = Self> {
+    /// Merges `other` into `self`, returning false if the local state did not change.
+    fn merge(&mut self, other: Rhs) -> bool;
+}
+
+impl<T: ?Sized + Merge<Rhs>, Rhs> Merge<Rhs> for Tagged<T> {
+    fn merge(&mut self, other: Rhs) -> bool {
+        (**self).merge(other)
+    }
+}
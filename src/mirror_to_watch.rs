@@ -0,0 +1,33 @@
+use crate::rw_lock::Inner;
+use futures::{try_ready, Async, Future, Poll};
+use futures_locks::RwLockReadFut;
+use std::sync::Arc;
+use tokio_sync::watch;
+
+/// A `Future` resolving to a `watch::Receiver` mirroring the value of a `RwLock`.
+///
+/// See `RwLock::mirror_to_watch`.
+pub struct MirrorToWatchFut<T> {
+    pub(crate) fut: RwLockReadFut<T>,
+    pub(crate) inner: Arc<Inner<T>>,
+}
+
+impl<T> Future for MirrorToWatchFut<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Item = watch::Receiver<T>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let guard = try_ready!(self.fut.poll());
+        let (mut tx, rx) = watch::channel((*guard).clone());
+
+        // registered while the read guard is held so no write can be missed.
+        self.inner.add_hook(Box::new(move |value: &T, _| {
+            tx.broadcast(value.clone()).is_ok()
+        }));
+
+        Ok(Async::Ready(rx))
+    }
+}
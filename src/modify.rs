@@ -0,0 +1,33 @@
+use crate::{RwLockWriteFut, SetTag};
+use futures::{try_ready, Async, Future, Poll};
+use version_tag::VersionTag;
+
+/// A `Future` applying a mutation under the write lock and committing it only if the
+/// mutation reports a change.
+///
+/// See `RwLock::modify`.
+pub struct ModifyFut<T: ?Sized + SetTag, F> {
+    pub(crate) f: Option<F>,
+    pub(crate) fut: RwLockWriteFut<T>,
+}
+
+impl<T, F> Future for ModifyFut<T, F>
+where
+    T: ?Sized + SetTag,
+    F: FnOnce(&mut T) -> bool,
+{
+    type Item = Option<VersionTag>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let mut guard = try_ready!(self.fut.poll());
+        let f = self.f.take().expect("Cannot poll twice");
+
+        if f(&mut guard) {
+            Ok(Async::Ready(Some(guard.finish())))
+        } else {
+            guard.discard();
+            Ok(Async::Ready(None))
+        }
+    }
+}
@@ -0,0 +1,32 @@
+/// A delta applied to a value under the write lock.
+///
+/// See `RwLock::apply_patch`.
+///
+/// # Example
+///
+/// ```
+/// use futures_tag_locks::{Patch, RwLock, Tagged};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// struct SetName(String);
+///
+/// impl Patch<Tagged<String>> for SetName {
+///     fn apply(self, value: &mut Tagged<String>) -> bool {
+///         if **value == self.0 {
+///             return false;
+///         }
+///
+///         **value = self.0;
+///         true
+///     }
+/// }
+///
+/// let lock = RwLock::new_tagged("a".to_string());
+///
+/// assert!(block_on_all(lock.apply_patch(SetName("b".into()))).unwrap().is_some());
+/// assert!(block_on_all(lock.apply_patch(SetName("b".into()))).unwrap().is_none());
+/// ```
+pub trait Patch<T: ?Sized> {
+    /// Applies the patch to `value`, returning false if it changed nothing.
+    fn apply(self, value: &mut T) -> bool;
+}
@@ -0,0 +1,57 @@
+use crate::RwLock;
+use futures::{try_ready, Async, Future, IntoFuture, Poll};
+use futures_locks::RwLockReadFut;
+use version_tag::VersionTag;
+
+/// A `Future` running an asynchronous step on a value read from the lock, and starting
+/// over until no write was committed while the step was running.
+///
+/// See `RwLock::read_latest`.
+pub struct ReadLatestFut<T: ?Sized, F, B: IntoFuture> {
+    pub(crate) lock: RwLock<T>,
+    pub(crate) state: ReadLatestState<T, B::Future>,
+    pub(crate) step: F,
+}
+
+pub(crate) enum ReadLatestState<T: ?Sized, F> {
+    Read(RwLockReadFut<T>),
+    Step(F, Option<VersionTag>),
+}
+
+impl<T, F, B> Future for ReadLatestFut<T, F, B>
+where
+    T: ?Sized,
+    F: FnMut(&T) -> B,
+    B: IntoFuture,
+{
+    type Item = (B::Item, Option<VersionTag>);
+    type Error = B::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            self.state = match &mut self.state {
+                ReadLatestState::Read(fut) => {
+                    let guard = match fut.poll() {
+                        Ok(Async::Ready(guard)) => guard,
+                        Ok(Async::NotReady) => return Ok(Async::NotReady),
+                        Err(_) => unreachable!("Lock error"),
+                    };
+
+                    let tag = self.lock.last_tag();
+                    let fut = (self.step)(&guard).into_future();
+
+                    ReadLatestState::Step(fut, tag)
+                }
+                ReadLatestState::Step(fut, tag) => {
+                    let item = try_ready!(fut.poll());
+
+                    if self.lock.last_tag() == *tag {
+                        return Ok(Async::Ready((item, *tag)));
+                    }
+
+                    ReadLatestState::Read(self.lock.read())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::RwLock;
+use futures::{Async, Future, Poll};
+use futures_locks::{RwLockReadFut, RwLockReadGuard};
+use version_tag::VersionTag;
+
+/// The number of times `RwLock::read_validated` re-acquires the lock after a failed
+/// validation.
+pub const READ_VALIDATED_RETRIES: usize = 3;
+
+/// A `Future` acquiring the read lock and validating the value, retrying while
+/// concurrent writers move the tag.
+///
+/// See `RwLock::read_validated`.
+pub struct ReadValidatedFut<T: ?Sized, F> {
+    pub(crate) fut: RwLockReadFut<T>,
+    pub(crate) lock: RwLock<T>,
+    pub(crate) retries: usize,
+    pub(crate) seen: Option<VersionTag>,
+    pub(crate) validate: F,
+}
+
+impl<T, F, E> Future for ReadValidatedFut<T, F>
+where
+    T: ?Sized,
+    F: FnMut(&T) -> Result<(), E>,
+{
+    type Item = (RwLockReadGuard<T>, Option<VersionTag>);
+    type Error = E;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let guard = match self.fut.poll() {
+                Ok(Async::Ready(guard)) => guard,
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(_) => unreachable!("Lock error"),
+            };
+
+            // No write can be committed while the read guard is held.
+            let tag = self.lock.last_tag();
+
+            match (self.validate)(&guard) {
+                Ok(()) => return Ok(Async::Ready((guard, tag))),
+                Err(e) if tag == self.seen || self.retries == 0 => return Err(e),
+                Err(_) => {
+                    drop(guard);
+                    self.retries -= 1;
+                    self.seen = tag;
+                    self.fut = self.lock.read();
+                }
+            }
+        }
+    }
+}
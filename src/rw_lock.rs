@@ -1,8 +1,34 @@
-use crate::SetTag;
+use crate::commit_fut::Finalizer;
+use crate::diagnostics::InitTimer;
+use crate::forward_tags::TagQueue;
+use crate::history::History;
+use crate::holder::{CallSite, HolderSlot};
+use crate::initializing::{InitGate, InitToken};
+use crate::long_hold::LongHoldWatch;
+use crate::read_latest::ReadLatestState;
+use crate::swap_fut::SwapState;
+use crate::sync::{AtomicUsize, Mutex, Ordering};
+use crate::tag_stream::TagWatch;
+#[cfg(feature = "debug-tracking")]
+use crate::Holder;
+#[cfg(feature = "watch")]
+use crate::MirrorToWatchFut;
+use crate::{
+    ChangeEvent, Clock, CommitFut, Diagnostic, DiagnosticEvent, DiagnosticStream, Expiring,
+    ForwardTags, FullPolicy, HistoryEntry, Initialized, IntentWriteFut, LockId, LongHold, Merge,
+    ModifyFut, Notified, Patch, QueueFull, ReadLatestFut, ReadValidatedFut, RwLockBuilder,
+    RwLockWriteIfFut, Scope, ScopeFuture, SetTag, SnapshotReceiver, SwapFut, SystemClock,
+    TagObserver, TagStream, Tagged, TaggedSink, ValueStream, WeakRwLock, WriteAndNotifyFut,
+    READ_VALIDATED_RETRIES,
+};
+use futures::sync::mpsc::{self, Sender, UnboundedSender};
 use futures::{try_ready, Async, Future, IntoFuture, Poll};
 use futures_locks::{self as locks, RwLockReadFut, RwLockReadGuard};
-use std::mem::replace;
+use std::mem::{replace, take};
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 use version_tag::VersionTag;
 
 /// A Futures-aware RwLock.
@@ -11,12 +37,210 @@ use version_tag::VersionTag;
 ///
 /// On Write, the data is tagged with a new version. We can cheaply detect changes and
 /// rebuild resources if he lock has been accessed on write since the last read.
-pub struct RwLock<T: ?Sized>(locks::RwLock<T>);
+///
+/// For a value `T: Send + Sync`, the lock is `Send` and `Sync` and its futures and guards,
+/// owning a handle on the lock, are `Send`.
+pub struct RwLock<T: ?Sized>(pub(crate) Arc<Inner<T>>);
 
 impl<T> RwLock<T> {
     /// Create a new `RwLock` in the unlocked state.
     pub fn new(value: T) -> Self {
-        Self(locks::RwLock::new(value))
+        Self(Arc::new(Inner::new(value)))
+    }
+
+    /// Create a new `RwLock` notifying its observers and tag streams once per `window`,
+    /// with the latest write. The hooks still run on every write.
+    pub fn with_coalescing(value: T, window: Duration) -> Self {
+        RwLockBuilder::new().coalesce(window).build(value)
+    }
+
+    /// Create a new `RwLock` reading the time from `clock` instead of the system clock.
+    ///
+    /// The clock drives the coalescing window, the expiration checks of `read_or_refresh`
+    /// and `write_or_refresh`, and the timestamps of the audit trail.
+    pub fn with_clock(value: T, clock: Arc<dyn Clock>) -> Self {
+        RwLockBuilder::new().clock(clock).build(value)
+    }
+
+    /// Swaps the values of this lock and `other`, bumping both tags.
+    ///
+    /// Both write locks are acquired, in the order of their `LockId`, before the values are
+    /// swapped, so no reader observes the same value in both locks or a value in neither.
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let blue = RwLock::new_tagged("v1");
+    /// let green = RwLock::new_tagged("v2");
+    ///
+    /// block_on_all(blue.swap_with(&green)).unwrap();
+    /// assert_eq!("v2", block_on_all(blue.read().map(|v| **v)).unwrap());
+    /// ```
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn swap_with(&self, other: &RwLock<T>) -> SwapFut<T>
+    where
+        T: SetTag,
+    {
+        let state = if self.id() == other.id() {
+            SwapState::Done
+        } else if self.id() < other.id() {
+            SwapState::First(self.write(), other.clone())
+        } else {
+            SwapState::First(other.write(), self.clone())
+        };
+
+        SwapFut { state }
+    }
+
+    pub(crate) fn from_builder(builder: RwLockBuilder, value: T) -> Self {
+        let mut inner = Inner::new(value);
+
+        if let Some(clock) = builder.clock {
+            inner.clock = clock;
+        }
+
+        inner.coalesce = builder.coalesce.map(Coalesce::new);
+        inner
+            .history
+            .lock()
+            .expect("Inner")
+            .set_capacity(builder.history);
+        inner.max_write_waiters = builder.max_write_waiters;
+        inner.name = builder.name;
+
+        Self(Arc::new(inner))
+    }
+
+    /// Returns a `Stream` receiving an `Arc` snapshot of the value and its tag on
+    /// every write, cloned once and shared by all the receivers.
+    ///
+    /// A receiver keeps the `capacity` latest snapshots, skipping the oldest ones.
+    pub fn broadcast_snapshots(&self, capacity: usize) -> SnapshotReceiver<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let (tx, rx) = SnapshotReceiver::new(capacity);
+
+        self.0
+            .add_snapshot_hook(Box::new(move |snapshot: &Arc<T>, tag| {
+                tx.send(tag, snapshot.clone())
+            }));
+
+        rx
+    }
+
+    /// Returns a `Stream` yielding the tag and a clone of the value for every write.
+    ///
+    /// No write is skipped, the values are buffered until the stream consumes them.
+    pub fn value_stream(&self) -> ValueStream<T>
+    where
+        T: Clone + Send + 'static,
+    {
+        let (tx, rx) = mpsc::unbounded();
+
+        self.0.add_hook(Box::new(move |value: &T, tag| {
+            tx.unbounded_send((tag, value.clone())).is_ok()
+        }));
+
+        ValueStream(rx)
+    }
+
+    /// Mirrors the value into a `tokio_sync::watch` channel updated on every write.
+    ///
+    /// The returned `Future` resolves to the receiving half once the current value has
+    /// been read. The mirroring stops when all the receivers have been dropped.
+    #[cfg(feature = "watch")]
+    pub fn mirror_to_watch(&self) -> MirrorToWatchFut<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        MirrorToWatchFut {
+            fut: self.0.lock.read(),
+            inner: self.0.clone(),
+        }
+    }
+
+    /// Registers a diff callback invoked on each write with the previous value, the new
+    /// value and the new tag.
+    ///
+    /// The previous value is cloned when the write lock is acquired, which allows
+    /// incremental downstream updates instead of full rebuilds.
+    pub fn on_diff<F>(&self, f: F)
+    where
+        F: FnMut(&T, &T, VersionTag) + Send + 'static,
+        T: Clone + Send + 'static,
+    {
+        let f = Arc::new(Mutex::new(f));
+
+        self.0
+            .captures
+            .lock()
+            .expect("Inner")
+            .push(Box::new(move |old: &T| {
+                let f = f.clone();
+                let old = old.clone();
+
+                Box::new(move |new: &T, tag| (f.lock().expect("on_diff"))(&old, new, tag))
+            }));
+    }
+
+    /// Returns a `Future` pushing every new tag into `sender`.
+    ///
+    /// The `policy` decides what happens to the tags when the channel is full.
+    /// The returned future must be spawned for the tags to be forwarded.
+    pub fn forward_tags(&self, sender: Sender<VersionTag>, policy: FullPolicy) -> ForwardTags<T> {
+        let queue = Arc::new(TagQueue::default());
+
+        self.observe(queue.clone());
+        ForwardTags::new(self.downgrade(), queue, self.subscribe(), sender, policy)
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Returns a handle on the underlying `futures_locks::RwLock`, shared with this lock.
+    ///
+    /// The accesses made through the returned handle bypass the tagging layer: the writes
+    /// do not bump the tag, nor notify the hooks, observers and streams of this lock.
+    pub fn into_inner_lock(self) -> locks::RwLock<T> {
+        self.0.lock.clone()
+    }
+
+    /// Returns the underlying `futures_locks::RwLock`.
+    ///
+    /// Only available with the `raw` feature. The accesses made through the raw lock
+    /// bypass the tagging layer: a write does not bump the tag, nor notify the hooks,
+    /// observers and streams of this lock. Reserved for the occasional untagged write.
+    #[cfg(feature = "raw")]
+    pub fn as_raw(&self) -> &locks::RwLock<T> {
+        &self.0.lock
+    }
+
+    /// Returns the current holder of the write lock, if any.
+    ///
+    /// Only available with the `debug-tracking` feature, which records where and when
+    /// each write guard was acquired.
+    #[cfg(feature = "debug-tracking")]
+    pub fn holder(&self) -> Option<Holder> {
+        self.0.holder.get()
+    }
+
+    /// Returns the unique identifier of the lock, shared by all its clones.
+    pub fn id(&self) -> LockId {
+        self.0.id
+    }
+
+    /// Returns the name given to the lock with `RwLockBuilder::name`.
+    pub fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    /// Create a `WeakRwLock` handle that does not keep the protected data alive.
+    ///
+    /// Once every `RwLock` handle has been dropped, `WeakRwLock::upgrade` returns `None`.
+    pub fn downgrade(&self) -> WeakRwLock<T> {
+        WeakRwLock(Arc::downgrade(&self.0))
     }
 
     /// Acquire the `RwLock` in read-only.
@@ -24,65 +248,1037 @@ impl<T> RwLock<T> {
     /// When the returned `Future` is ready, then this task will have read-only
     /// access to the protected data.
     pub fn read(&self) -> RwLockReadFut<T> {
-        self.0.read()
+        self.0.lock.read()
     }
 
     /// Acquire the `RwLock` in exclusive read-write mode.
     ///
     /// When the returned `Future` is ready, then this task will have read-write
     /// access to the protected data.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
     pub fn write(&self) -> RwLockWriteFut<T>
     where
         T: SetTag,
     {
-        RwLockWriteFut(self.0.write())
+        self.0.write_waiters.fetch_add(1, Ordering::SeqCst);
+        RwLockWriteFut::new(self.0.clone())
+    }
+
+    /// Runs `f` with a `Scope` handing out guards that the compiler keeps from escaping
+    /// the returned `Future`.
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(1);
+    ///
+    /// let fut = lock.scope(|scope| {
+    ///     Box::new(scope.write().map(|mut w| {
+    ///         **w += 1;
+    ///         **w
+    ///     }))
+    /// });
+    ///
+    /// assert_eq!(2, block_on_all(fut).unwrap());
+    /// ```
+    ///
+    /// A guard cannot be the output of the scope:
+    ///
+    /// ```compile_fail
+    /// use futures_tag_locks::RwLock;
+    ///
+    /// let lock = RwLock::new_tagged(1);
+    /// let fut = lock.scope(|scope| Box::new(scope.read()));
+    /// ```
+    pub fn scope<F, R, E>(&self, f: F) -> ScopeFuture<'static, R, E>
+    where
+        F: for<'s> FnOnce(Scope<'s, T>) -> ScopeFuture<'s, R, E>,
+    {
+        f(Scope::new(self.clone()))
+    }
+
+    /// Acquire the write intent of the `RwLock`.
+    ///
+    /// Only one task holds the intent at a time, without blocking the readers, so two
+    /// upgrading readers cannot deadlock.
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(None);
+    ///
+    /// let fut = lock.intent_write().and_then(|intent| {
+    ///     intent.read().map(|v| v.is_none()).and_then(move |stale| {
+    ///         intent.upgrade().map(move |mut w| {
+    ///             if stale {
+    ///                 **w = Some(42);
+    ///             }
+    ///         })
+    ///     })
+    /// });
+    ///
+    /// block_on_all(fut).unwrap();
+    /// ```
+    pub fn intent_write(&self) -> IntentWriteFut<T> {
+        IntentWriteFut {
+            fut: self.0.intent.lock(),
+            lock: self.clone(),
+        }
+    }
+
+    /// Same as `write`: the lock is only requested on the first poll of the `Future`.
+    ///
+    /// A queued writer does not hold back the new readers of the underlying
+    /// `futures_locks::RwLock`, so requesting the write early would not drain the readers
+    /// sooner; it would only take the lock before the caller is ready to write.
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(1);
+    /// let read = block_on_all(lock.read()).unwrap();
+    /// let write = lock.request_write();
+    ///
+    /// drop(read);
+    /// block_on_all(write.map(|mut w| **w = 2)).unwrap();
+    /// ```
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn request_write(&self) -> RwLockWriteFut<T>
+    where
+        T: SetTag,
+    {
+        self.write()
+    }
+
+    /// Like `write`, but fails with `QueueFull` if the number of writers waiting on
+    /// the lock has reached the limit set with `RwLockBuilder::max_write_waiters`.
+    ///
+    /// Every pending write counts toward the limit, whether it was queued with `write`
+    /// or with this method.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn queue_write(&self) -> Result<RwLockWriteFut<T>, QueueFull>
+    where
+        T: SetTag,
+    {
+        let max = match self.0.max_write_waiters {
+            Some(max) => max,
+            None => return Ok(self.write()),
+        };
+
+        let mut waiters = self.0.write_waiters.load(Ordering::SeqCst);
+
+        loop {
+            if waiters >= max {
+                self.0.diagnose(DiagnosticEvent::QueueFull { max });
+
+                return Err(QueueFull {
+                    id: self.0.id,
+                    max,
+                    name: self.0.name.clone(),
+                });
+            }
+
+            match self.0.write_waiters.compare_exchange(
+                waiters,
+                waiters + 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return Ok(RwLockWriteFut::new(self.0.clone())),
+                Err(actual) => waiters = actual,
+            }
+        }
+    }
+
+    /// Reads the value, then runs an asynchronous step on it without holding the lock.
+    ///
+    /// The sequence starts over whenever a write lands during the step, and resolves with
+    /// the result of the step and the tag it was computed from.
+    ///
+    /// ```
+    /// use futures::future::ok;
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(21);
+    /// let fut = lock.read_latest(|v| {
+    ///     let v = **v;
+    ///     ok::<_, ()>(v * 2)
+    /// });
+    ///
+    /// assert_eq!(42, block_on_all(fut).unwrap().0);
+    /// ```
+    pub fn read_latest<F, B>(&self, step: F) -> ReadLatestFut<T, F, B>
+    where
+        F: FnMut(&T) -> B,
+        B: IntoFuture,
+    {
+        ReadLatestFut {
+            lock: self.clone(),
+            state: ReadLatestState::Read(self.read()),
+            step,
+        }
+    }
+
+    /// Acquire the `RwLock` in read-only and run `validate` on the value.
+    ///
+    /// A failed validation is retried, up to `READ_VALIDATED_RETRIES` times, as long as a
+    /// writer moved the tag since the previous attempt.
+    ///
+    /// ```
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(vec![1, 2]);
+    /// let fut = lock.read_validated(|v| if v.len() == 2 { Ok(()) } else { Err("partial") });
+    /// let (guard, tag) = block_on_all(fut).unwrap();
+    ///
+    /// assert_eq!(2, guard.len());
+    /// assert_eq!(None, tag);
+    /// ```
+    pub fn read_validated<F, E>(&self, validate: F) -> ReadValidatedFut<T, F>
+    where
+        F: FnMut(&T) -> Result<(), E>,
+    {
+        ReadValidatedFut {
+            fut: self.read(),
+            lock: self.clone(),
+            retries: READ_VALIDATED_RETRIES,
+            seen: self.last_tag(),
+            validate,
+        }
+    }
+
+    /// Returns the tag committed by the last write, or `None` if the lock has never
+    /// been written.
+    ///
+    /// This does not enter the waiter queue of the lock.
+    pub fn last_tag(&self) -> Option<VersionTag> {
+        self.0.watch.tag()
+    }
+
+    /// Returns true if no write has been committed since the write that produced `seen`.
+    ///
+    /// A caller keeping its own snapshot of the value along with the tag can skip the
+    /// lock entirely, for example in per-request cache checks:
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::RwLock;
+    /// use std::sync::Arc;
+    ///
+    /// let lock = RwLock::new_tagged(10);
+    /// let seen = lock.write().map(|mut w| { **w = 12; w.new_tag() }).wait().unwrap();
+    /// let snapshot = Arc::new(12);
+    ///
+    /// if lock.is_unchanged(seen) {
+    ///     assert_eq!(12, *snapshot);
+    /// }
+    /// ```
+    pub fn is_unchanged(&self, seen: VersionTag) -> bool {
+        self.last_tag() == Some(seen)
+    }
+
+    /// Returns a `Stream` of the tags committed by the subsequent writes.
+    ///
+    /// The stream conflates the tags, yielding only the latest one to a slow consumer.
+    pub fn subscribe(&self) -> TagStream {
+        TagWatch::subscribe(&self.0.watch, self.0.clock.clone())
+    }
+
+    /// Sends the tag and a serialized form of the value into `sender` on each commit, so
+    /// a follower can mirror the state and stay in sync by comparing the tags.
+    ///
+    /// `serialize` runs inside the critical section and emits a snapshot or a delta. The
+    /// replication stops when the receiver is dropped.
+    ///
+    /// ```
+    /// use futures::sync::mpsc;
+    /// use futures::{Future, Stream};
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(10);
+    /// let (tx, rx) = mpsc::unbounded();
+    ///
+    /// lock.replicate(tx, |v| v.to_string().into_bytes());
+    /// block_on_all(lock.write().map(|mut w| **w = 12)).unwrap();
+    ///
+    /// let (event, _) = block_on_all(rx.into_future()).ok().unwrap();
+    /// assert_eq!(b"12".to_vec(), event.unwrap().1);
+    /// ```
+    pub fn replicate<F, B>(&self, sender: UnboundedSender<(VersionTag, B)>, mut serialize: F)
+    where
+        F: FnMut(&T) -> B + Send + 'static,
+        B: Send + 'static,
+    {
+        self.0.add_hook(Box::new(move |value: &T, tag| {
+            sender.unbounded_send((tag, serialize(value))).is_ok()
+        }));
+    }
+
+    /// Registers a callback invoked with the value and its new tag inside the critical
+    /// section of each write, right after the tag is applied.
+    pub fn on_write<F>(&self, mut f: F)
+    where
+        F: FnMut(&T, VersionTag) + Send + 'static,
+    {
+        self.0.add_hook(Box::new(move |value: &T, tag| {
+            f(value, tag);
+            true
+        }));
+    }
+
+    /// Like `on_write`, but the callback runs just after the write lock is released, on
+    /// a snapshot of the committed value shared with the other snapshot consumers.
+    pub fn on_write_unlocked<F>(&self, mut f: F)
+    where
+        F: FnMut(&T, VersionTag) + Send + 'static,
+        T: Clone,
+    {
+        self.0.enable_snapshot();
+        self.0.unlocked_hooks.lock().expect("Inner").push(Box::new(
+            move |snapshot: &Arc<T>, tag| {
+                f(snapshot, tag);
+                true
+            },
+        ));
+    }
+
+    /// Acquires the write lock and hands out the guard only if `predicate` holds on the
+    /// value, otherwise releases it without bumping the tag.
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(10);
+    /// let before = block_on_all(lock.read().map(|v| v.tag())).unwrap();
+    /// let guard = block_on_all(lock.write_if(|v| **v < 5)).unwrap();
+    ///
+    /// assert!(guard.is_none());
+    /// assert_eq!(before, block_on_all(lock.read().map(|v| v.tag())).unwrap());
+    /// ```
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn write_if<P>(&self, predicate: P) -> RwLockWriteIfFut<T, P>
+    where
+        P: FnOnce(&T) -> bool,
+        T: SetTag,
+    {
+        RwLockWriteIfFut {
+            fut: self.write(),
+            predicate: Some(predicate),
+        }
+    }
+
+    /// Acquires the write lock and applies `f` to the value, committing the write only if
+    /// `f` returns true.
+    ///
+    /// When `f` reports the mutation was a no-op, the lock is released without bumping
+    /// the tag. The `Future` resolves to the committed tag, if any.
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(vec![1, 2]);
+    /// let tag = block_on_all(lock.modify(|v| {
+    ///     let len = v.len();
+    ///     v.dedup();
+    ///     v.len() != len
+    /// })).unwrap();
+    ///
+    /// assert_eq!(None, tag);
+    /// ```
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn modify<F>(&self, f: F) -> ModifyFut<T, F>
+    where
+        F: FnOnce(&mut T) -> bool,
+        T: SetTag,
+    {
+        ModifyFut {
+            f: Some(f),
+            fut: self.write(),
+        }
+    }
+
+    /// Acquires the write lock and merges `other` into the value, committing the write
+    /// only if the merge changed the local state.
+    ///
+    /// See `modify`.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn merge<O>(&self, other: O) -> ModifyFut<T, impl FnOnce(&mut T) -> bool>
+    where
+        T: Merge<O> + SetTag,
+    {
+        self.modify(move |value| value.merge(other))
+    }
+
+    /// Acquires the write lock and applies `patch` to the value, committing the write only
+    /// if the patch reports a change.
+    ///
+    /// See `modify`.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn apply_patch<P>(&self, patch: P) -> ModifyFut<T, impl FnOnce(&mut T) -> bool>
+    where
+        P: Patch<T>,
+        T: SetTag,
+    {
+        self.modify(move |value| patch.apply(value))
+    }
+
+    /// Acquires the write lock, applies `f` to the value and commits the write.
+    ///
+    /// The returned `Future` resolves to the number of hooks, observers and tag streams
+    /// notified by the commit, to verify the wiring of the notifications in tests or to
+    /// feed metrics.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn write_and_notify<F>(&self, f: F) -> WriteAndNotifyFut<T, F>
+    where
+        F: FnOnce(&mut T),
+        T: SetTag,
+    {
+        WriteAndNotifyFut {
+            f: Some(f),
+            fut: self.write(),
+        }
+    }
+
+    /// Registers an asynchronous finalizer run by `RwLockWriteGuard::commit` before the
+    /// new tag is applied, to flush the value to disk for example.
+    ///
+    /// The writes committed by dropping the guard do not run the finalizers.
+    pub fn on_commit<F, FUT>(&self, mut f: F)
+    where
+        F: FnMut(&T, VersionTag) -> FUT + Send + 'static,
+        FUT: IntoFuture<Item = (), Error = ()>,
+        FUT::Future: Send + 'static,
+    {
+        self.0
+            .finalizers
+            .lock()
+            .expect("Inner")
+            .push(Box::new(move |value: &T, tag| {
+                Box::new(f(value, tag).into_future())
+            }));
+    }
+
+    /// Keeps an in-memory audit trail of the last `capacity` writes.
+    ///
+    /// A `capacity` of 0 disables the audit trail. See `history`.
+    pub fn keep_history(&self, capacity: usize) {
+        self.0.history.lock().expect("Inner").set_capacity(capacity);
+    }
+
+    /// Returns the audit trail of the last writes, oldest first.
+    ///
+    /// The audit trail is empty unless enabled with `keep_history`.
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        self.0.history.lock().expect("Inner").entries()
+    }
+
+    /// Returns the entry of the last write in the audit trail: when it was committed,
+    /// by which actor, with which label and reason.
+    ///
+    /// Returns `None` unless the audit trail is enabled with `keep_history`.
+    pub fn last_writer(&self) -> Option<HistoryEntry> {
+        self.0.history.lock().expect("Inner").last()
+    }
+
+    /// Reports the write guards held longer than `threshold` to `report`.
+    ///
+    /// The hold is only measured when the guard is released, so a guard that is never
+    /// released is never reported. The report carries the name of the lock, to catch
+    /// guards accidentally held across slow operations. Replaces any previous callback.
+    pub fn on_long_hold<F>(&self, threshold: Duration, report: F)
+    where
+        F: Fn(&LongHold) + Send + Sync + 'static,
+    {
+        *self.0.long_hold.lock().expect("Inner") = Some(LongHoldWatch {
+            report: Arc::new(report),
+            threshold,
+        });
+    }
+
+    /// Returns a `Stream` of the diagnostic events of this lock, buffered until consumed
+    /// and ending when the lock is dropped.
+    ///
+    /// ```
+    /// use futures::{Future, Stream};
+    /// use futures_tag_locks::{DiagnosticEvent, RwLock};
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(10);
+    /// let events = lock.diagnostics();
+    ///
+    /// block_on_all(lock.write().map(|mut w| **w = 12)).unwrap();
+    /// drop(lock);
+    ///
+    /// let events = block_on_all(events.map(|d| d.event).collect()).unwrap();
+    /// assert_eq!(DiagnosticEvent::Acquired, events[0]);
+    /// ```
+    pub fn diagnostics(&self) -> DiagnosticStream {
+        let (tx, rx) = mpsc::unbounded();
+        self.0.diagnostics.lock().expect("Inner").push(tx);
+        DiagnosticStream(rx)
+    }
+
+    /// Registers an observer notified of every tag committed on this lock.
+    pub fn observe(&self, observer: Arc<dyn TagObserver>) {
+        self.0.observers.lock().expect("Inner").push(observer);
+    }
+
+    /// Removes an observer previously registered with `observe`.
+    ///
+    /// Returns false if the observer was not registered.
+    pub fn unobserve(&self, observer: &Arc<dyn TagObserver>) -> bool {
+        let mut observers = self.0.observers.lock().expect("Inner");
+        let len = observers.len();
+
+        observers.retain(|o| !Arc::ptr_eq(o, observer));
+        observers.len() != len
     }
 }
 
 impl<T> RwLock<Option<T>> {
+    /// Returns true while an initializer started by `read_or_init` or `write_or_init`
+    /// is running.
+    ///
+    /// A reader can check this before calling `read_or_init` to serve a fallback
+    /// instead of queueing behind a slow first load.
+    pub fn is_initializing(&self) -> bool {
+        self.0.init_gate.is_active()
+    }
+
+    /// Returns a `Future` resolving once no initializer is running.
+    ///
+    /// The future waits on a notification, not on the write lock held by the initializer.
+    pub fn initialized(&self) -> Initialized {
+        InitGate::wait(&self.0.init_gate)
+    }
+
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
     pub fn read_or_init<F, FUT>(&self, init: F) -> RwLockReadInitFut<F, FUT>
     where
         F: Fn() -> FUT,
         FUT: IntoFuture<Item = T>,
     {
         RwLockReadInitFut {
+            gate: self.0.init_gate.clone(),
             init,
-            lock: self.0.clone(),
-            state: RwLockReadInitState::Read(self.0.read()),
+            inner: self.0.clone(),
+            is_ready: |v, _| v.is_some(),
+            new_guard: RwLockWriteGuard::untagged,
+            on_init: |_, _, _| {},
+            refresh_early: None,
+            site: CallSite::caller(),
+            state: RwLockReadInitState::Read(self.0.lock.read()),
+            timer: InitTimer::new(),
         }
     }
 
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
     pub fn write_or_init<F, FUT>(&self, init: F) -> RwLockWriteInitFut<F, FUT>
     where
         F: Fn() -> FUT,
         FUT: IntoFuture<Item = T>,
+        T: SetTag,
     {
         RwLockWriteInitFut {
+            gate: self.0.init_gate.clone(),
             init,
-            state: RwLockWriteInitState::Write(self.0.write()),
+            inner: self.0.clone(),
+            is_ready: |v, _| v.is_some(),
+            on_init: |_, _, _| {},
+            site: CallSite::caller(),
+            state: RwLockWriteInitState::Write(self.0.lock.write()),
+            timer: InitTimer::new(),
         }
     }
 }
 
+impl<T> RwLock<Option<Expiring<T>>> {
+    /// Like `read_or_init`, but an expired value is re-initialized as if it was missing.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn read_or_refresh<F, FUT>(&self, init: F) -> RwLockReadInitFut<F, FUT>
+    where
+        F: Fn() -> FUT,
+        FUT: IntoFuture<Item = Expiring<T>>,
+    {
+        RwLockReadInitFut {
+            gate: self.0.init_gate.clone(),
+            init,
+            inner: self.0.clone(),
+            is_ready: is_fresh,
+            new_guard: RwLockWriteGuard::new,
+            on_init: record_init,
+            refresh_early: None,
+            site: CallSite::caller(),
+            state: RwLockReadInitState::Read(self.0.lock.read()),
+            timer: InitTimer::new(),
+        }
+    }
+
+    /// Like `read_or_refresh`, but a value close to its deadline may be refreshed early,
+    /// before it expires.
+    ///
+    /// The winner of the draw refreshes outside of the lock while the other readers keep
+    /// the current value, and drops the result if a write was committed meanwhile.
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::{Expiring, RwLock};
+    /// use std::time::Duration;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new(None);
+    /// let init = || Ok::<_, ()>(Expiring::new(10, Duration::from_secs(60)));
+    ///
+    /// let value = block_on_all(lock.read_or_refresh_early(init).map(|v| **v)).unwrap();
+    /// assert_eq!(10, value);
+    /// ```
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn read_or_refresh_early<F, FUT>(&self, init: F) -> RwLockReadInitFut<F, FUT>
+    where
+        F: Fn() -> FUT,
+        FUT: IntoFuture<Item = Expiring<T>>,
+    {
+        RwLockReadInitFut {
+            gate: self.0.init_gate.clone(),
+            init,
+            inner: self.0.clone(),
+            is_ready: is_fresh,
+            new_guard: RwLockWriteGuard::new,
+            on_init: record_init,
+            refresh_early: Some(should_refresh_early),
+            site: CallSite::caller(),
+            state: RwLockReadInitState::Read(self.0.lock.read()),
+            timer: InitTimer::new(),
+        }
+    }
+
+    /// Like `write_or_init`, but an expired value is re-initialized as if it was missing.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn write_or_refresh<F, FUT>(&self, init: F) -> RwLockWriteInitFut<F, FUT>
+    where
+        F: Fn() -> FUT,
+        FUT: IntoFuture<Item = Expiring<T>>,
+    {
+        RwLockWriteInitFut {
+            gate: self.0.init_gate.clone(),
+            init,
+            inner: self.0.clone(),
+            is_ready: is_fresh,
+            on_init: record_init,
+            site: CallSite::caller(),
+            state: RwLockWriteInitState::Write(self.0.lock.write()),
+            timer: InitTimer::new(),
+        }
+    }
+}
+
+fn is_fresh<T>(value: &Option<Expiring<T>>, now: Instant) -> bool {
+    value.as_ref().is_some_and(|v| !v.is_expired_at(now))
+}
+
+/// The `beta` used by `RwLock::read_or_refresh_early`, as recommended by the XFetch paper.
+pub const XFETCH_BETA: f64 = 1.0;
+
+fn should_refresh_early<T>(value: &Option<Expiring<T>>, clock: &dyn Clock) -> bool {
+    value
+        .as_ref()
+        .is_some_and(|v| v.should_refresh_early(clock, XFETCH_BETA))
+}
+
+fn record_init<T>(value: &mut Option<Expiring<T>>, delta: Duration, now: Instant) {
+    if let Some(v) = value {
+        v.initialized(delta, now);
+    }
+}
+
+impl<T> RwLock<Tagged<T>> {
+    /// Create a new `RwLock` protecting a `Tagged` value.
+    ///
+    /// This is a shortcut for `RwLock::new(Tagged::new(value))`.
+    pub fn new_tagged(value: T) -> Self {
+        Self::new(Tagged::new(value))
+    }
+
+    /// Returns a `Sink` replacing the value and bumping the tag for each item sent.
+    pub fn sink(&self) -> TaggedSink<T> {
+        TaggedSink::new(self.clone())
+    }
+}
+
+/// Adopts an existing `futures_locks::RwLock`, keeping its outstanding clones.
+///
+/// The accesses made through the other clones of the `futures_locks::RwLock` bypass the
+/// tagging layer.
+impl<T: ?Sized> From<locks::RwLock<T>> for RwLock<T> {
+    fn from(lock: locks::RwLock<T>) -> Self {
+        Self(Arc::new(Inner::from_lock(lock)))
+    }
+}
+
 impl<T: ?Sized> Clone for RwLock<T> {
     fn clone(&self) -> Self {
         Self(self.0.clone())
     }
 }
 
-impl<T: ?Sized> Default for RwLock<T>
+impl<T> Default for RwLock<T>
 where
     T: Default,
 {
     fn default() -> Self {
-        Self(locks::RwLock::new(Default::default()))
+        Self::new(Default::default())
+    }
+}
+
+struct Coalesce {
+    pending: Arc<Mutex<Option<ChangeEvent>>>,
+    window: Duration,
+}
+
+impl Coalesce {
+    fn new(window: Duration) -> Self {
+        Self {
+            pending: Default::default(),
+            window,
+        }
+    }
+
+    /// Holds `event` back until the end of the burst; the first event of a burst schedules
+    /// the notification of the latest one.
+    fn defer(
+        &self,
+        event: ChangeEvent,
+        clock: &dyn Clock,
+        observers: &Observers,
+        watch: &Arc<TagWatch>,
+    ) {
+        watch.set(event.tag);
+
+        if self
+            .pending
+            .lock()
+            .expect("Coalesce")
+            .replace(event)
+            .is_some()
+        {
+            return;
+        }
+
+        let pending = self.pending.clone();
+        let observers = observers.clone();
+        let watch = watch.clone();
+
+        clock.schedule(
+            clock.now() + self.window,
+            Box::new(move || {
+                let event = pending.lock().expect("Coalesce").take();
+
+                if let Some(event) = event {
+                    notify_observers(&observers, &event);
+                    watch.wake();
+                }
+            }),
+        );
+    }
+}
+
+type Observers = Arc<Mutex<Vec<Arc<dyn TagObserver>>>>;
+
+/// Runs the hooks and returns how many were run, none while panicking.
+///
+/// The hooks run outside of the mutex, so a panicking hook does not poison it and a hook
+/// can register other hooks.
+fn run_hooks<V: ?Sized>(hooks: &Mutex<Vec<Hook<V>>>, value: &V, tag: VersionTag) -> usize {
+    if thread::panicking() {
+        return 0;
+    }
+
+    let mut running = RunningHooks {
+        hooks: take(&mut *hooks.lock().expect("Inner")),
+        registry: hooks,
+    };
+
+    let len = running.hooks.len();
+    running.hooks.retain_mut(|hook| hook(value, tag));
+    len
+}
+
+/// Gives the hooks back to the registry, even if one of them panics.
+struct RunningHooks<'a, V: ?Sized> {
+    hooks: Vec<Hook<V>>,
+    registry: &'a Mutex<Vec<Hook<V>>>,
+}
+
+impl<V: ?Sized> Drop for RunningHooks<'_, V> {
+    fn drop(&mut self) {
+        let mut registry = self.registry.lock().expect("Inner");
+        let added = replace(&mut *registry, take(&mut self.hooks));
+
+        registry.extend(added);
+    }
+}
+
+fn notify_observers(observers: &Observers, event: &ChangeEvent) -> usize {
+    let observers = observers.lock().expect("Inner").clone();
+    observers.iter().for_each(|o| o.on_change(event));
+    observers.len()
+}
+
+type Capture<T> = Box<dyn FnMut(&T) -> Commit<T> + Send>;
+type Commit<T> = Box<dyn FnOnce(&T, VersionTag) + Send>;
+pub(crate) type Hook<T> = Box<dyn FnMut(&T, VersionTag) -> bool + Send>;
+type IsReady<T> = fn(&Option<T>, Instant) -> bool;
+type RefreshEarly<T> = fn(&Option<T>, &dyn Clock) -> bool;
+type NewGuard<T> = fn(locks::RwLockWriteGuard<T>, Arc<Inner<T>>, CallSite) -> RwLockWriteGuard<T>;
+type Snapshot<T> = fn(&T) -> Arc<T>;
+
+pub(crate) struct Inner<T: ?Sized> {
+    captures: Mutex<Vec<Capture<T>>>,
+    clock: Arc<dyn Clock>,
+    coalesce: Option<Coalesce>,
+    diagnostics: Mutex<Vec<UnboundedSender<Diagnostic>>>,
+    finalizers: Mutex<Vec<Finalizer<T>>>,
+    history: Mutex<History>,
+    holder: HolderSlot,
+    hooks: Mutex<Vec<Hook<T>>>,
+    id: LockId,
+    init_gate: Arc<InitGate>,
+    intent: locks::Mutex<()>,
+    long_hold: Mutex<Option<LongHoldWatch>>,
+    max_write_waiters: Option<usize>,
+    name: Option<String>,
+    observers: Observers,
+    snapshot: Mutex<Option<Snapshot<T>>>,
+    snapshot_hooks: Mutex<Vec<Hook<Arc<T>>>>,
+    unlocked_hooks: Mutex<Vec<Hook<Arc<T>>>>,
+    write_waiters: AtomicUsize,
+    watch: Arc<TagWatch>,
+    pub(crate) lock: locks::RwLock<T>,
+}
+
+impl<T> Inner<T> {
+    fn new(value: T) -> Self {
+        Self::from_lock(locks::RwLock::new(value))
+    }
+}
+
+impl<T: ?Sized> Inner<T> {
+    fn from_lock(lock: locks::RwLock<T>) -> Self {
+        Self {
+            captures: Default::default(),
+            clock: SystemClock::shared(),
+            coalesce: None,
+            diagnostics: Default::default(),
+            finalizers: Default::default(),
+            history: Default::default(),
+            holder: Default::default(),
+            hooks: Default::default(),
+            id: LockId::next(),
+            init_gate: Default::default(),
+            intent: locks::Mutex::new(()),
+            long_hold: Default::default(),
+            max_write_waiters: None,
+            name: None,
+            observers: Default::default(),
+            snapshot: Default::default(),
+            snapshot_hooks: Default::default(),
+            unlocked_hooks: Default::default(),
+            write_waiters: AtomicUsize::new(0),
+            lock,
+            watch: Default::default(),
+        }
+    }
+
+    /// Gives a recycled lock a fresh identity, as if it had just been created around its
+    /// current value: nothing registered by the previous owner carries over.
+    ///
+    /// The shared parts are reset in place, unless a stream or future still holds them.
+    pub(crate) fn reset(&mut self) {
+        let Self {
+            captures,
+            clock,
+            coalesce,
+            diagnostics,
+            finalizers,
+            history,
+            holder,
+            hooks,
+            id,
+            init_gate,
+            intent,
+            long_hold,
+            max_write_waiters,
+            name,
+            observers,
+            snapshot,
+            snapshot_hooks,
+            unlocked_hooks,
+            write_waiters,
+            watch,
+            lock: _,
+        } = self;
+
+        watch.close();
+        reset_shared(watch);
+        reset_shared(init_gate);
+        reset_shared(observers);
+
+        if intent.get_mut().is_none() {
+            *intent = locks::Mutex::new(());
+        }
+
+        *captures = Default::default();
+        *clock = SystemClock::shared();
+        *coalesce = None;
+        *diagnostics = Default::default();
+        *finalizers = Default::default();
+        *history = Default::default();
+        *holder = Default::default();
+        *hooks = Default::default();
+        *id = LockId::next();
+        *long_hold = Default::default();
+        *max_write_waiters = None;
+        *name = None;
+        *snapshot = Default::default();
+        *snapshot_hooks = Default::default();
+        *unlocked_hooks = Default::default();
+        *write_waiters = AtomicUsize::new(0);
+    }
+}
+
+/// Resets the value behind `arc` in place, or replaces `arc` if it is still shared.
+fn reset_shared<V: Default>(arc: &mut Arc<V>) {
+    match Arc::get_mut(arc) {
+        Some(v) => *v = V::default(),
+        None => *arc = Default::default(),
+    }
+}
+
+impl<T: ?Sized> Inner<T> {
+    /// Registers a hook called on each commit; the hook is removed once it returns false.
+    pub(crate) fn add_hook(&self, hook: Hook<T>) {
+        self.hooks.lock().expect("Inner").push(hook);
+    }
+
+    /// Registers a hook called on each commit with an `Arc` snapshot of the value, cloned
+    /// once per commit and shared by all the snapshot hooks.
+    pub(crate) fn add_snapshot_hook(&self, hook: Hook<Arc<T>>)
+    where
+        T: Clone,
+    {
+        self.enable_snapshot();
+        self.snapshot_hooks.lock().expect("Inner").push(hook);
+    }
+
+    fn enable_snapshot(&self)
+    where
+        T: Clone,
+    {
+        *self.snapshot.lock().expect("Inner") = Some(|value| Arc::new(value.clone()));
+    }
+
+    fn capture(&self, value: &T) -> Vec<Commit<T>> {
+        self.captures
+            .lock()
+            .expect("Inner")
+            .iter_mut()
+            .map(|capture| capture(value))
+            .collect()
+    }
+
+    /// Notifies the observers and the streams, or defers it to the end of the burst when
+    /// coalescing. Returns how many observers and streams were notified right away.
+    fn notify(&self, event: ChangeEvent) -> (usize, usize) {
+        match &self.coalesce {
+            Some(coalesce) => {
+                coalesce.defer(event, &*self.clock, &self.observers, &self.watch);
+                (0, 0)
+            }
+            None => (
+                notify_observers(&self.observers, &event),
+                self.watch.publish(event.tag),
+            ),
+        }
+    }
+
+    /// Sends `event` to the diagnostic streams, dropping the closed ones.
+    fn diagnose(&self, event: DiagnosticEvent) {
+        let mut streams = self.diagnostics.lock().expect("Inner");
+
+        if streams.is_empty() {
+            return;
+        }
+
+        let diagnostic = Diagnostic {
+            event,
+            id: self.id,
+            name: self.name.clone(),
+        };
+
+        streams.retain(|s| s.unbounded_send(diagnostic.clone()).is_ok());
+    }
+
+    fn check_long_hold(&self, acquired: Instant, tag: VersionTag) {
+        let held = self.clock.now().duration_since(acquired);
+
+        // The report runs outside of the mutex, so it can replace the callback.
+        let report = match &*self.long_hold.lock().expect("Inner") {
+            Some(watch) if held > watch.threshold => watch.report.clone(),
+            _ => return,
+        };
+
+        self.diagnose(DiagnosticEvent::LongHold { held, tag });
+
+        report(&LongHold {
+            held,
+            id: self.id,
+            name: self.name.clone(),
+            tag,
+        });
+    }
+
+    /// Takes the snapshot of the committed value, if a snapshot or unlocked hook needs it.
+    fn snapshot(&self, value: &T) -> Option<Arc<T>> {
+        if self.snapshot_hooks.lock().expect("Inner").is_empty()
+            && self.unlocked_hooks.lock().expect("Inner").is_empty()
+        {
+            return None;
+        }
+
+        (*self.snapshot.lock().expect("Inner")).map(|snapshot| snapshot(value))
+    }
+}
+
+impl<T: ?Sized> Drop for Inner<T> {
+    fn drop(&mut self) {
+        self.watch.close();
     }
 }
 
 pub struct RwLockReadInitFut<F, FUT: IntoFuture> {
+    gate: Arc<InitGate>,
     init: F,
-    lock: locks::RwLock<Option<FUT::Item>>,
+    inner: Arc<Inner<Option<FUT::Item>>>,
+    is_ready: IsReady<FUT::Item>,
+    new_guard: NewGuard<Option<FUT::Item>>,
+    on_init: fn(&mut Option<FUT::Item>, Duration, Instant),
+    refresh_early: Option<RefreshEarly<FUT::Item>>,
+    site: CallSite,
     state: RwLockReadInitState<FUT>,
+    timer: InitTimer,
 }
 
 impl<F, FUT> Future for RwLockReadInitFut<F, FUT>
@@ -94,30 +1290,104 @@ where
     type Error = FUT::Error;
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        self.timer.start(&*self.inner.clock, self.gate.is_active());
+
         loop {
             let state = match &mut self.state {
-                RwLockReadInitState::Init(guard, f) => {
+                RwLockReadInitState::Init(pending, f, _) => {
                     let v = try_ready!(f.poll());
-                    **guard = Some(v);
-                    RwLockReadInitState::Read(self.lock.read())
+                    let now = self.inner.clock.now();
+                    let init = self.timer.init_done(now);
+                    let mut guard = pending.take();
+
+                    *guard = Some(v);
+                    (self.on_init)(&mut guard, init, now);
+                    self.inner.diagnose(self.timer.event(init));
+                    drop(guard);
+                    RwLockReadInitState::Read(self.inner.lock.read())
                 }
                 RwLockReadInitState::Read(f) => match f.poll() {
                     Ok(Async::Ready(guard)) => {
-                        if guard.is_some() {
-                            return Ok(Async::Ready(RwLockReadInitGuard(guard)));
+                        let now = self.inner.clock.now();
+
+                        if (self.is_ready)(&guard, now) {
+                            if let Some(event) = self.timer.by_other(now) {
+                                self.inner.diagnose(event);
+                            }
+
+                            let refresh = self.refresh_early.take();
+                            let token = match refresh {
+                                Some(f) if f(&guard, &*self.inner.clock) => {
+                                    InitGate::try_enter(&self.gate)
+                                }
+                                _ => None,
+                            };
+
+                            let token = match token {
+                                Some(token) => token,
+                                None => return Ok(Async::Ready(RwLockReadInitGuard(guard))),
+                            };
+
+                            self.timer.init_started(now);
+                            RwLockReadInitState::Refresh(
+                                (self.init)().into_future(),
+                                self.inner.watch.tag(),
+                                Some(token),
+                            )
+                        } else {
+                            RwLockReadInitState::Write(self.inner.lock.write())
                         }
+                    }
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(_) => unreachable!("Lock error"),
+                },
+                RwLockReadInitState::Refresh(f, seen, token) => match f.poll() {
+                    Ok(Async::Ready(v)) => RwLockReadInitState::Swap(
+                        self.inner.lock.write(),
+                        *seen,
+                        Some(v),
+                        token.take(),
+                    ),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(_) => RwLockReadInitState::Read(self.inner.lock.read()),
+                },
+                RwLockReadInitState::Swap(f, seen, v, _) => match f.poll() {
+                    // a write committed during the refresh wins over the refreshed value.
+                    Ok(Async::Ready(_)) if self.inner.watch.tag() != *seen => {
+                        RwLockReadInitState::Read(self.inner.lock.read())
+                    }
+                    Ok(Async::Ready(guard)) => {
+                        let now = self.inner.clock.now();
+                        let init = self.timer.init_done(now);
+                        let mut guard = (self.new_guard)(guard, self.inner.clone(), self.site);
 
-                        RwLockReadInitState::Write(self.lock.write())
+                        *guard = v.take();
+                        (self.on_init)(&mut guard, init, now);
+                        self.inner.diagnose(self.timer.event(init));
+                        drop(guard);
+                        RwLockReadInitState::Read(self.inner.lock.read())
                     }
                     Ok(Async::NotReady) => return Ok(Async::NotReady),
                     Err(_) => unreachable!("Lock error"),
                 },
                 RwLockReadInitState::Write(f) => match f.poll() {
                     Ok(Async::Ready(guard)) => {
-                        if guard.is_some() {
-                            RwLockReadInitState::Read(self.lock.read())
+                        let now = self.inner.clock.now();
+
+                        if (self.is_ready)(&guard, now) {
+                            self.timer.contend();
+                            RwLockReadInitState::Read(self.inner.lock.read())
                         } else {
-                            RwLockReadInitState::Init(guard, (self.init)().into_future())
+                            self.timer.init_started(now);
+                            RwLockReadInitState::Init(
+                                PendingInit::new((self.new_guard)(
+                                    guard,
+                                    self.inner.clone(),
+                                    self.site,
+                                )),
+                                (self.init)().into_future(),
+                                InitGate::enter(&self.gate),
+                            )
                         }
                     }
                     Ok(Async::NotReady) => return Ok(Async::NotReady),
@@ -134,7 +1404,7 @@ where
 ///
 /// If the data is not available,
 /// eg: value is `None`, the value is initialized.
-pub struct RwLockReadInitGuard<T>(RwLockReadGuard<Option<T>>);
+pub struct RwLockReadInitGuard<T>(pub(crate) RwLockReadGuard<Option<T>>);
 
 impl<T> Deref for RwLockReadInitGuard<T> {
     type Target = T;
@@ -144,66 +1414,335 @@ impl<T> Deref for RwLockReadInitGuard<T> {
 }
 
 enum RwLockReadInitState<FUT: IntoFuture> {
-    Init(locks::RwLockWriteGuard<Option<FUT::Item>>, FUT::Future),
+    Init(
+        PendingInit<Option<FUT::Item>>,
+        FUT::Future,
+        #[allow(dead_code)] InitToken,
+    ),
     Read(RwLockReadFut<Option<FUT::Item>>),
+    Refresh(FUT::Future, Option<VersionTag>, Option<InitToken>),
+    Swap(
+        locks::RwLockWriteFut<Option<FUT::Item>>,
+        Option<VersionTag>,
+        Option<FUT::Item>,
+        #[allow(dead_code)] Option<InitToken>,
+    ),
     Write(locks::RwLockWriteFut<Option<FUT::Item>>),
 }
 
-pub struct RwLockWriteFut<T: ?Sized + SetTag>(locks::RwLockWriteFut<T>);
+/// The write guard of a running initializer, committed through `take` once the value is
+/// set, or discarded without bumping the tag if the initializer fails or is dropped.
+struct PendingInit<T: ?Sized>(Option<RwLockWriteGuard<T>>);
+
+impl<T: ?Sized> PendingInit<T> {
+    fn new(guard: RwLockWriteGuard<T>) -> Self {
+        Self(Some(guard))
+    }
+
+    fn take(&mut self) -> RwLockWriteGuard<T> {
+        self.0.take().expect("PendingInit")
+    }
+}
+
+impl<T: ?Sized> Drop for PendingInit<T> {
+    fn drop(&mut self) {
+        if let Some(guard) = self.0.take() {
+            guard.discard();
+        }
+    }
+}
+
+pub struct RwLockWriteFut<T: ?Sized + SetTag> {
+    fut: locks::RwLockWriteFut<T>,
+    inner: Arc<Inner<T>>,
+    queued: bool,
+    site: CallSite,
+}
+
+impl<T: ?Sized + SetTag> RwLockWriteFut<T> {
+    /// The caller must have counted this future in the write waiters of the lock.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    fn new(inner: Arc<Inner<T>>) -> Self {
+        Self {
+            fut: inner.lock.write(),
+            inner,
+            queued: true,
+            site: CallSite::caller(),
+        }
+    }
+
+    fn dequeue(&mut self) {
+        if self.queued {
+            self.queued = false;
+            self.inner.write_waiters.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
 
 impl<T: ?Sized + SetTag> Future for RwLockWriteFut<T> {
     type Item = RwLockWriteGuard<T>;
     type Error = ();
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        Ok(Async::Ready(RwLockWriteGuard::new(try_ready!(self
-            .0
-            .poll()))))
+        let guard = try_ready!(self.fut.poll());
+        self.dequeue();
+        Ok(Async::Ready(RwLockWriteGuard::new(
+            guard,
+            self.inner.clone(),
+            self.site,
+        )))
+    }
+}
+
+impl<T: ?Sized + SetTag> Drop for RwLockWriteFut<T> {
+    fn drop(&mut self) {
+        self.dequeue();
     }
 }
 
 /// An RAII guard, much like `std::sync::RwLockWriteGuard`.  The wrapped data
 /// can be accessed via its `Deref`  and `DerefMut` implementations.
-pub struct RwLockWriteGuard<T: ?Sized + SetTag> {
-    guard: locks::RwLockWriteGuard<T>,
+pub struct RwLockWriteGuard<T: ?Sized> {
+    acquired: Instant,
+    actor: Option<String>,
+    discard_changes: fn(&mut T),
+    guard: Option<locks::RwLockWriteGuard<T>>,
+    inner: Arc<Inner<T>>,
+    label: Option<String>,
     new_tag: VersionTag,
+    pending: Vec<Commit<T>>,
+    reason: Option<&'static str>,
+    set_tag: fn(&mut T, VersionTag),
 }
 
 impl<T: ?Sized + SetTag> RwLockWriteGuard<T> {
-    fn new(guard: locks::RwLockWriteGuard<T>) -> Self {
+    fn new(guard: locks::RwLockWriteGuard<T>, inner: Arc<Inner<T>>, site: CallSite) -> Self {
+        Self::with_tagging(guard, inner, site, T::set_tag, T::discard_changes)
+    }
+
+    /// Returns a `Future` running the finalizers registered with `RwLock::on_commit`,
+    /// then committing the write and resolving to its tag.
+    ///
+    /// This complements the commit made when the guard is dropped, for the asynchronous
+    /// work that must complete before the new tag becomes visible.
+    pub fn commit(self) -> CommitFut<T> {
+        let tag = self.new_tag;
+
+        // The finalizers run outside of the mutex, so they can register other ones.
+        let mut finalizers = take(&mut *self.inner.finalizers.lock().expect("Inner"));
+        let pending = finalizers.iter_mut().map(|f| f(&self, tag)).collect();
+        let mut registered = self.inner.finalizers.lock().expect("Inner");
+
+        finalizers.append(&mut registered);
+        *registered = finalizers;
+        drop(registered);
+
+        CommitFut {
+            guard: Some(self),
+            pending,
+        }
+    }
+}
+
+impl<T: ?Sized> RwLockWriteGuard<T> {
+    /// A guard committing the lock tag without tagging the value, for values that do
+    /// not implement `SetTag`.
+    fn untagged(guard: locks::RwLockWriteGuard<T>, inner: Arc<Inner<T>>, site: CallSite) -> Self {
+        Self::with_tagging(guard, inner, site, |_, _| {}, |_| {})
+    }
+
+    fn with_tagging(
+        guard: locks::RwLockWriteGuard<T>,
+        inner: Arc<Inner<T>>,
+        site: CallSite,
+        set_tag: fn(&mut T, VersionTag),
+        discard_changes: fn(&mut T),
+    ) -> Self {
+        let acquired = inner.clock.now();
+        inner.holder.acquire(site, acquired);
+        inner.diagnose(DiagnosticEvent::Acquired);
+
         Self {
-            guard,
+            acquired,
+            actor: None,
+            discard_changes,
             new_tag: VersionTag::new(),
+            pending: inner.capture(&guard),
+            guard: Some(guard),
+            inner,
+            label: None,
+            reason: None,
+            set_tag,
+        }
+    }
+
+    /// Commits the write and releases the lock, once. A writer panicking releases the
+    /// lock without committing, since the value may be half written.
+    fn release(&mut self) -> Option<Notified> {
+        let tag = self.new_tag;
+        let mut guard = self.guard.take()?;
+
+        if thread::panicking() {
+            self.inner.diagnose(DiagnosticEvent::Poisoned);
+            self.release_uncommitted(guard);
+            return None;
+        }
+
+        (self.set_tag)(&mut guard, tag);
+        self.pending
+            .drain(..)
+            .for_each(|commit| commit(&guard, tag));
+
+        let mut hooks = run_hooks(&self.inner.hooks, &guard, tag);
+
+        let snapshot = self.inner.snapshot(&guard);
+
+        if let Some(snapshot) = &snapshot {
+            hooks += run_hooks(&self.inner.snapshot_hooks, snapshot, tag);
         }
+
+        let event = ChangeEvent {
+            tag,
+            at: self.inner.clock.system_now(),
+            actor: self.actor.take(),
+            label: self.label.take(),
+            reason: self.reason,
+        };
+
+        self.inner.history.lock().expect("Inner").push(&event);
+
+        let (observers, streams) = self.inner.notify(event);
+
+        self.inner.holder.release();
+        drop(guard);
+
+        self.inner.diagnose(DiagnosticEvent::Released {
+            held: self.inner.clock.now().duration_since(self.acquired),
+            tag: Some(tag),
+        });
+
+        self.inner.check_long_hold(self.acquired, tag);
+
+        if let Some(snapshot) = &snapshot {
+            hooks += run_hooks(&self.inner.unlocked_hooks, snapshot, tag);
+        }
+
+        Some(Notified {
+            hooks,
+            observers,
+            streams,
+            tag,
+        })
+    }
+
+    /// Releases the lock without committing: the tag is not bumped and nobody is
+    /// notified. The value must be left unchanged, the changes it recorded are discarded.
+    pub(crate) fn discard(mut self) {
+        if let Some(guard) = self.guard.take() {
+            self.release_uncommitted(guard);
+        }
+    }
+
+    fn release_uncommitted(&mut self, mut guard: locks::RwLockWriteGuard<T>) {
+        (self.discard_changes)(&mut guard);
+        self.inner.holder.release();
+        drop(guard);
+
+        self.inner.diagnose(DiagnosticEvent::Released {
+            held: self.inner.clock.now().duration_since(self.acquired),
+            tag: None,
+        });
+    }
+
+    /// Commits the write with `tag` instead of a new tag, to carry over the tag of
+    /// another lock.
+    pub(crate) fn set_new_tag(&mut self, tag: VersionTag) {
+        self.new_tag = tag;
     }
 
     pub fn new_tag(&self) -> VersionTag {
         self.new_tag
     }
+
+    /// Commits the write, releases the lock and returns the committed tag.
+    ///
+    /// This is the same as dropping the guard, without having to read `new_tag` first.
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures_tag_locks::RwLock;
+    /// use tokio::executor::current_thread::block_on_all;
+    ///
+    /// let lock = RwLock::new_tagged(10);
+    /// let tag = block_on_all(lock.write().map(|mut w| {
+    ///     **w = 12;
+    ///     w.finish()
+    /// })).unwrap();
+    ///
+    /// assert_eq!(tag, block_on_all(lock.read().map(|v| v.tag())).unwrap());
+    /// ```
+    pub fn finish(self) -> VersionTag {
+        self.new_tag
+    }
+
+    /// Like `finish`, but reports how many hooks, observers and tag streams were
+    /// notified by the commit.
+    pub fn finish_and_notify(mut self) -> Notified {
+        self.release().expect("RwLockWriteGuard")
+    }
+
+    /// Sets the actor performing this write, recorded in the audit trail of the lock.
+    ///
+    /// See `RwLock::last_writer`.
+    pub fn set_actor<A: Into<String>>(&mut self, actor: A) {
+        self.actor = Some(actor.into());
+    }
+
+    /// Sets a label recorded with this write in the audit trail of the lock.
+    pub fn set_label<L: Into<String>>(&mut self, label: L) {
+        self.label = Some(label.into());
+    }
+
+    /// Sets the reason of this write, recorded in the audit trail of the lock and
+    /// delivered to the observers with the new tag.
+    ///
+    /// Lets downstream systems tell, for example, user edits from programmatic refreshes.
+    pub fn set_reason(&mut self, reason: &'static str) {
+        self.reason = Some(reason);
+    }
 }
 
-impl<T: ?Sized + SetTag> Deref for RwLockWriteGuard<T> {
+impl<T: ?Sized> Deref for RwLockWriteGuard<T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
-        &self.guard
+        self.guard.as_ref().expect("RwLockWriteGuard")
     }
 }
 
-impl<T: ?Sized + SetTag> DerefMut for RwLockWriteGuard<T> {
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.guard
+        self.guard.as_mut().expect("RwLockWriteGuard")
     }
 }
 
-impl<T: ?Sized + SetTag> Drop for RwLockWriteGuard<T> {
+impl<T: ?Sized> Drop for RwLockWriteGuard<T> {
     fn drop(&mut self) {
-        self.guard.set_tag(self.new_tag);
+        self.release();
     }
 }
 
-pub struct RwLockWriteInitFut<F, FUT: IntoFuture> {
+pub struct RwLockWriteInitFut<F, FUT: IntoFuture>
+where
+    FUT::Item: SetTag,
+{
+    gate: Arc<InitGate>,
     init: F,
+    inner: Arc<Inner<Option<FUT::Item>>>,
+    is_ready: fn(&Option<FUT::Item>, Instant) -> bool,
+    on_init: fn(&mut Option<FUT::Item>, Duration, Instant),
+    site: CallSite,
     state: RwLockWriteInitState<FUT>,
+    timer: InitTimer,
 }
 
 impl<F, FUT> Future for RwLockWriteInitFut<F, FUT>
@@ -216,27 +1755,53 @@ where
     type Error = FUT::Error;
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        self.timer.start(&*self.inner.clock, self.gate.is_active());
+
         loop {
             match replace(&mut self.state, RwLockWriteInitState::Done) {
                 RwLockWriteInitState::Done => panic!("Cannot poll twice"),
-                RwLockWriteInitState::Init(mut guard, mut f) => match f.poll() {
+                RwLockWriteInitState::Init(mut pending, mut f, token) => match f.poll() {
                     Ok(Async::NotReady) => {
-                        self.state = RwLockWriteInitState::Init(guard, f);
+                        self.state = RwLockWriteInitState::Init(pending, f, token);
                         return Ok(Async::NotReady);
                     }
                     Ok(Async::Ready(v)) => {
+                        let now = self.inner.clock.now();
+                        let init = self.timer.init_done(now);
+                        let mut guard = pending.take();
+
                         *guard = Some(v);
-                        return Ok(Async::Ready(RwLockWriteGuard::new(guard)));
+                        (self.on_init)(&mut guard, init, now);
+                        self.inner.diagnose(self.timer.event(init));
+                        return Ok(Async::Ready(guard));
                     }
                     Err(e) => return Err(e),
                 },
                 RwLockWriteInitState::Write(mut f) => match f.poll() {
                     Ok(Async::Ready(guard)) => {
-                        if guard.is_some() {
-                            return Ok(Async::Ready(RwLockWriteGuard::new(guard)));
+                        let now = self.inner.clock.now();
+
+                        if (self.is_ready)(&guard, now) {
+                            if let Some(event) = self.timer.by_other(now) {
+                                self.inner.diagnose(event);
+                            }
+
+                            return Ok(Async::Ready(RwLockWriteGuard::new(
+                                guard,
+                                self.inner.clone(),
+                                self.site,
+                            )));
                         } else {
-                            self.state =
-                                RwLockWriteInitState::Init(guard, (self.init)().into_future());
+                            self.timer.init_started(now);
+                            self.state = RwLockWriteInitState::Init(
+                                PendingInit::new(RwLockWriteGuard::new(
+                                    guard,
+                                    self.inner.clone(),
+                                    self.site,
+                                )),
+                                (self.init)().into_future(),
+                                InitGate::enter(&self.gate),
+                            );
                             continue;
                         }
                     }
@@ -251,8 +1816,11 @@ where
     }
 }
 
-enum RwLockWriteInitState<FUT: IntoFuture> {
+enum RwLockWriteInitState<FUT: IntoFuture>
+where
+    FUT::Item: SetTag,
+{
     Done,
-    Init(locks::RwLockWriteGuard<Option<FUT::Item>>, FUT::Future),
+    Init(PendingInit<Option<FUT::Item>>, FUT::Future, InitToken),
     Write(locks::RwLockWriteFut<Option<FUT::Item>>),
 }
@@ -0,0 +1,73 @@
+use crate::{Clock, RwLock};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A builder to create a `RwLock` with non-default options.
+///
+/// # Example
+///
+/// ```
+/// use futures_tag_locks::{RwLockBuilder, Tagged};
+/// use std::time::Duration;
+///
+/// let lock = RwLockBuilder::new()
+///     .name("config")
+///     .coalesce(Duration::from_millis(50))
+///     .history(16)
+///     .build(Tagged::new(10));
+///
+/// assert_eq!(Some("config"), lock.name());
+/// ```
+#[derive(Default)]
+pub struct RwLockBuilder {
+    pub(crate) clock: Option<Arc<dyn Clock>>,
+    pub(crate) coalesce: Option<Duration>,
+    pub(crate) history: usize,
+    pub(crate) max_write_waiters: Option<usize>,
+    pub(crate) name: Option<String>,
+}
+
+impl RwLockBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the time from `clock` instead of the system clock. See `RwLock::with_clock`.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Coalesces the notifications of the writes occurring within `window`. See
+    /// `RwLock::with_coalescing`.
+    ///
+    /// The end of each burst is scheduled on the clock of the lock; with the system clock,
+    /// the observers are then notified from the shared timer thread of the crate.
+    pub fn coalesce(mut self, window: Duration) -> Self {
+        self.coalesce = Some(window);
+        self
+    }
+
+    /// Keeps an audit trail of the last `capacity` writes. See `RwLock::keep_history`.
+    pub fn history(mut self, capacity: usize) -> Self {
+        self.history = capacity;
+        self
+    }
+
+    /// Limits the number of writers waiting on the lock. See `RwLock::queue_write`.
+    pub fn max_write_waiters(mut self, max: usize) -> Self {
+        self.max_write_waiters = Some(max);
+        self
+    }
+
+    /// Names the lock, making it identifiable in diagnostics.
+    pub fn name<N: Into<String>>(mut self, name: N) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Create the `RwLock` protecting `value`.
+    pub fn build<T>(self, value: T) -> RwLock<T> {
+        RwLock::from_builder(self, value)
+    }
+}
@@ -0,0 +1,72 @@
+use crate::rw_lock::Inner;
+use crate::sync::Mutex;
+use crate::RwLock;
+use std::sync::Arc;
+
+/// A pool that recycles the internal allocations of `RwLock`s.
+///
+/// A lock given back with `recycle` and no longer shared starts over like a new one,
+/// its value dropped and replaced by its default.
+///
+/// # Example
+///
+/// ```
+/// use futures_tag_locks::{RwLockPool, Tagged};
+///
+/// let pool = RwLockPool::new(16);
+/// let lock = pool.get(Tagged::new(10));
+///
+/// // the allocation will be reused by the next call to get.
+/// pool.recycle(lock);
+/// let _lock = pool.get(Tagged::new(12));
+/// ```
+pub struct RwLockPool<T> {
+    capacity: usize,
+    free: Mutex<Vec<Arc<Inner<T>>>>,
+}
+
+impl<T> RwLockPool<T> {
+    /// Create a new pool keeping at most `capacity` unused locks.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            free: Mutex::new(Vec::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns a `RwLock` protecting `value`, reusing a recycled lock when available.
+    pub fn get(&self, value: T) -> RwLock<T> {
+        let recycled = self.free.lock().expect("RwLockPool").pop();
+
+        if let Some(mut lock) = recycled {
+            if let Some(v) = Arc::get_mut(&mut lock).and_then(|i| i.lock.get_mut()) {
+                *v = value;
+                return RwLock(lock);
+            }
+        }
+
+        RwLock::new(value)
+    }
+}
+
+impl<T: Default> RwLockPool<T> {
+    /// Gives back a `RwLock` to the pool.
+    ///
+    /// The lock is kept only if this is the last handle on it and the pool is not full.
+    pub fn recycle(&self, lock: RwLock<T>) {
+        let mut lock = lock.0;
+        let mut free = self.free.lock().expect("RwLockPool");
+
+        if free.len() >= self.capacity {
+            return;
+        }
+
+        if let Some(inner) = Arc::get_mut(&mut lock) {
+            if let Some(value) = inner.lock.get_mut() {
+                *value = T::default();
+                inner.reset();
+                free.push(lock);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{RwLock, RwLockWriteFut, RwLockWriteGuard, SetTag};
+use futures::{try_ready, Async, Future, Poll};
+use futures_locks::{RwLockReadFut, RwLockReadGuard};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+/// A boxed `Future` running inside a `Scope`.
+///
+/// See `RwLock::scope`.
+pub type ScopeFuture<'s, R, E> = Box<dyn Future<Item = R, Error = E> + Send + 's>;
+
+/// Ties the lifetime `'s` to the scope; invariant so it cannot be widened or narrowed.
+type ScopeMarker<'s> = PhantomData<fn(&'s ()) -> &'s ()>;
+
+/// A handle acquiring guards that cannot outlive the scope they were created in.
+///
+/// See `RwLock::scope`.
+pub struct Scope<'s, T: ?Sized> {
+    lock: RwLock<T>,
+    marker: ScopeMarker<'s>,
+}
+
+impl<'s, T: ?Sized> Scope<'s, T> {
+    pub(crate) fn new(lock: RwLock<T>) -> Self {
+        Self {
+            lock,
+            marker: PhantomData,
+        }
+    }
+
+    /// Acquire the `RwLock` in read-only, for the duration of the scope at most.
+    pub fn read(&self) -> ScopedReadFut<'s, T> {
+        ScopedReadFut(self.lock.read(), PhantomData)
+    }
+
+    /// Acquire the `RwLock` in exclusive read-write mode, for the duration of the scope
+    /// at most.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn write(&self) -> ScopedWriteFut<'s, T>
+    where
+        T: SetTag,
+    {
+        ScopedWriteFut(self.lock.write(), PhantomData)
+    }
+}
+
+/// A `Future` acquiring a `ScopedReadGuard`.
+pub struct ScopedReadFut<'s, T: ?Sized>(RwLockReadFut<T>, ScopeMarker<'s>);
+
+impl<'s, T: ?Sized> Future for ScopedReadFut<'s, T> {
+    type Item = ScopedReadGuard<'s, T>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let guard = try_ready!(self.0.poll());
+        Ok(Async::Ready(ScopedReadGuard(guard, PhantomData)))
+    }
+}
+
+/// A read guard bound to the `Scope` it was acquired in.
+pub struct ScopedReadGuard<'s, T: ?Sized>(RwLockReadGuard<T>, ScopeMarker<'s>);
+
+impl<'s, T: ?Sized> Deref for ScopedReadGuard<'s, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A `Future` acquiring a `ScopedWriteGuard`.
+pub struct ScopedWriteFut<'s, T: ?Sized + SetTag>(RwLockWriteFut<T>, ScopeMarker<'s>);
+
+impl<'s, T: ?Sized + SetTag> Future for ScopedWriteFut<'s, T> {
+    type Item = ScopedWriteGuard<'s, T>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let guard = try_ready!(self.0.poll());
+        Ok(Async::Ready(ScopedWriteGuard(guard, PhantomData)))
+    }
+}
+
+/// A write guard bound to the `Scope` it was acquired in.
+///
+/// The write is committed when the guard is dropped, like `RwLockWriteGuard`.
+pub struct ScopedWriteGuard<'s, T: ?Sized + SetTag>(RwLockWriteGuard<T>, ScopeMarker<'s>);
+
+impl<'s, T: ?Sized + SetTag> Deref for ScopedWriteGuard<'s, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'s, T: ?Sized + SetTag> DerefMut for ScopedWriteGuard<'s, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,37 @@
+//! Compile-time checks of the `Send` and `Sync` properties documented on `RwLock`.
+//!
+//! A change making one of these types lose `Send` or `Sync` fails the build here rather
+//! than in the code of the users spawning futures holding them.
+
+use crate::*;
+use futures::future::FutureResult;
+
+fn send<T: Send>() {}
+fn send_sync<T: Send + Sync>() {}
+
+type Value = Tagged<Vec<u8>>;
+type Init = fn() -> FutureResult<Value, ()>;
+
+const _: fn() = || {
+    send_sync::<RwLock<Value>>();
+    send_sync::<WeakRwLock<Value>>();
+    send_sync::<RwLockPool<Value>>();
+    send_sync::<ConfigLock<Value>>();
+    send_sync::<StaticTagLock<Value>>();
+
+    send::<RwLockReadFut<Value>>();
+    send::<RwLockReadGuard<Value>>();
+    send::<RwLockWriteFut<Value>>();
+    send::<RwLockWriteGuard<Value>>();
+    send::<RwLockReadInitFut<Init, FutureResult<Value, ()>>>();
+    send::<RwLockReadInitGuard<Value>>();
+    send::<RwLockWriteInitFut<Init, FutureResult<Value, ()>>>();
+
+    send::<CommitFut<Value>>();
+    send::<Initialized>();
+    send::<WriteAndNotifyFut<Value, fn(&mut Value)>>();
+    send::<SnapshotReceiver<Value>>();
+    send::<TagStream>();
+    send::<TaggedSink<Value>>();
+    send::<ValueStream<Value>>();
+};
@@ -1,7 +1,58 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, PoisonError};
 use version_tag::VersionTag;
 
+/// A value that can be stamped with the tag of the write that modified it.
+///
+/// The trait is object safe, so heterogenous values can share a lock behind a `Box`:
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{RwLock, SetTag, Tagged};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// trait Plugin: SetTag + Send + Sync {
+///     fn run(&mut self);
+/// }
+///
+/// impl Plugin for Tagged<u32> {
+///     fn run(&mut self) {
+///         **self += 1;
+///     }
+/// }
+///
+/// let plugin: Box<dyn Plugin> = Box::new(Tagged::new(0u32));
+/// let lock = RwLock::new(plugin);
+///
+/// block_on_all(lock.write().map(|mut w| w.run())).unwrap();
 pub trait SetTag {
     fn set_tag(&mut self, tag: VersionTag);
+
+    /// Forgets the changes recorded since the last `set_tag`, called when a write is
+    /// discarded without committing. The default implementation does nothing.
+    fn discard_changes(&mut self) {}
+}
+
+impl<T: ?Sized + SetTag> SetTag for Box<T> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        (**self).set_tag(tag);
+    }
+
+    fn discard_changes(&mut self) {
+        (**self).discard_changes();
+    }
+}
+
+impl<T: ?Sized + SetTag> SetTag for &mut T {
+    fn set_tag(&mut self, tag: VersionTag) {
+        (**self).set_tag(tag);
+    }
+
+    fn discard_changes(&mut self) {
+        (**self).discard_changes();
+    }
 }
 
 impl<T: SetTag> SetTag for Option<T> {
@@ -10,4 +61,102 @@ impl<T: SetTag> SetTag for Option<T> {
             v.set_tag(tag);
         }
     }
+
+    fn discard_changes(&mut self) {
+        if let Some(v) = self {
+            v.discard_changes();
+        }
+    }
+}
+
+/// Tags the `Ok` value.
+///
+/// An error is not a version of the value and is left untagged. A reader caching a
+/// failed fetch as `Err` detects the writes replacing it with `RwLock::last_tag`, which
+/// every write advances.
+impl<T: SetTag, E> SetTag for Result<T, E> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        if let Ok(v) = self {
+            v.set_tag(tag);
+        }
+    }
+
+    fn discard_changes(&mut self) {
+        if let Ok(v) = self {
+            v.discard_changes();
+        }
+    }
+}
+
+/// Tags the owned value; a borrowed value cannot have been modified by the write.
+impl<B> SetTag for Cow<'_, B>
+where
+    B: ?Sized + ToOwned,
+    B::Owned: SetTag,
+{
+    fn set_tag(&mut self, tag: VersionTag) {
+        if let Cow::Owned(v) = self {
+            v.set_tag(tag);
+        }
+    }
+
+    fn discard_changes(&mut self) {
+        if let Cow::Owned(v) = self {
+            v.discard_changes();
+        }
+    }
+}
+
+impl<T: ?Sized + SetTag> SetTag for RefCell<T> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        self.get_mut().set_tag(tag);
+    }
+
+    fn discard_changes(&mut self) {
+        self.get_mut().discard_changes();
+    }
+}
+
+impl<T: ?Sized + SetTag> SetTag for Mutex<T> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        self.get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_tag(tag);
+    }
+
+    fn discard_changes(&mut self) {
+        self.get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .discard_changes();
+    }
+}
+
+/// Tags the shared value.
+///
+/// # Panics
+///
+/// Panics if the value is borrowed when the write guard is dropped.
+impl<T: ?Sized + SetTag> SetTag for Rc<RefCell<T>> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        self.borrow_mut().set_tag(tag);
+    }
+
+    fn discard_changes(&mut self) {
+        self.borrow_mut().discard_changes();
+    }
+}
+
+/// Tags the shared value, locking the mutex when the write guard is dropped.
+impl<T: ?Sized + SetTag> SetTag for Arc<Mutex<T>> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_tag(tag);
+    }
+
+    fn discard_changes(&mut self) {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .discard_changes();
+    }
 }
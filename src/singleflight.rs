@@ -0,0 +1,118 @@
+use crate::sync::Mutex;
+use crate::{SetTag, Tagged};
+use futures::future::{self, Shared};
+use futures::{Async, Future, IntoFuture, Poll};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Weak};
+use version_tag::VersionTag;
+
+type Flight<V, E> = Shared<Box<dyn Future<Item = (V, VersionTag), Error = E> + Send>>;
+
+/// Deduplicates concurrent computations sharing the same key.
+///
+/// The callers of an in-flight key share its result, tagged with the same `VersionTag`;
+/// the next call after it completes starts a new computation.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::Singleflight;
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let flights = Singleflight::new();
+/// let a = flights.call("user", || Ok::<_, ()>(10));
+/// let b = flights.call("user", || Ok::<_, ()>(12));
+///
+/// let (a, b) = block_on_all(a.join(b)).unwrap();
+///
+/// assert_eq!(10, *b);
+/// assert_eq!(a.tag(), b.tag());
+/// ```
+pub struct Singleflight<K, V, E> {
+    flights: Arc<Mutex<HashMap<K, Flight<V, E>>>>,
+}
+
+impl<K, V, E> Singleflight<K, V, E>
+where
+    K: Clone + Eq + Hash + Send + 'static,
+    V: Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        Self {
+            flights: Default::default(),
+        }
+    }
+
+    /// Returns the result of the computation in flight for `key`, starting it with `f`
+    /// if there is none.
+    ///
+    /// `f` is not called if a computation is already in flight for `key`.
+    pub fn call<F, FUT>(&self, key: K, f: F) -> SingleflightFut<V, E>
+    where
+        F: FnOnce() -> FUT + Send + 'static,
+        FUT: IntoFuture<Item = V, Error = E> + 'static,
+        FUT::Future: Send + 'static,
+    {
+        let mut flights = self.flights.lock().expect("Singleflight");
+
+        let flight = flights.entry(key.clone()).or_insert_with(|| {
+            let weak = Arc::downgrade(&self.flights);
+            let fut: Box<dyn Future<Item = (V, VersionTag), Error = E> + Send> =
+                Box::new(future::lazy(f).then(move |r| {
+                    land(&weak, &key);
+                    r.map(|v| (v, VersionTag::new()))
+                }));
+
+            fut.shared()
+        });
+
+        SingleflightFut(flight.clone())
+    }
+
+    /// Returns true if a computation is in flight for `key`.
+    pub fn is_in_flight(&self, key: &K) -> bool {
+        self.flights.lock().expect("Singleflight").contains_key(key)
+    }
+}
+
+impl<K, V, E> Default for Singleflight<K, V, E>
+where
+    K: Clone + Eq + Hash + Send + 'static,
+    V: Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn land<K: Eq + Hash, V, E>(flights: &Weak<Mutex<HashMap<K, Flight<V, E>>>>, key: &K) {
+    if let Some(flights) = flights.upgrade() {
+        flights.lock().expect("Singleflight").remove(key);
+    }
+}
+
+/// A `Future` resolving to the shared result of a `Singleflight` computation.
+pub struct SingleflightFut<V, E>(Flight<V, E>);
+
+impl<V: Clone, E: Clone> Future for SingleflightFut<V, E> {
+    type Item = Tagged<V>;
+    type Error = E;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.0.poll() {
+            Ok(Async::Ready(item)) => {
+                let (value, tag) = &*item;
+                let mut tagged = Tagged::new(value.clone());
+
+                tagged.set_tag(*tag);
+                Ok(Async::Ready(tagged))
+            }
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(e) => Err((*e).clone()),
+        }
+    }
+}
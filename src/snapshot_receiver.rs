@@ -0,0 +1,110 @@
+use crate::sync::Mutex;
+use futures::task::{self, Task};
+use futures::{Async, Poll, Stream};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use version_tag::VersionTag;
+
+/// A `Stream` of the snapshots published by a `RwLock` on every write.
+///
+/// A receiver falling behind by more than its capacity drops the oldest snapshots.
+///
+/// See `RwLock::broadcast_snapshots`.
+pub struct SnapshotReceiver<T>(Arc<SnapshotQueue<T>>);
+
+impl<T> SnapshotReceiver<T> {
+    pub(crate) fn new(capacity: usize) -> (SnapshotSender<T>, Self) {
+        let queue = Arc::new(SnapshotQueue::new(capacity));
+        (SnapshotSender(queue.clone()), Self(queue))
+    }
+}
+
+impl<T> Stream for SnapshotReceiver<T> {
+    type Item = (VersionTag, Arc<T>);
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let mut state = self.0.state.lock().expect("SnapshotReceiver");
+
+        match state.snapshots.pop_front() {
+            Some(snapshot) => Ok(Async::Ready(Some(snapshot))),
+            None if state.closed => Ok(Async::Ready(None)),
+            None => {
+                state.task = Some(task::current());
+                Ok(Async::NotReady)
+            }
+        }
+    }
+}
+
+/// Publishes the snapshots to a `SnapshotReceiver`, ending its stream when dropped.
+pub(crate) struct SnapshotSender<T>(Arc<SnapshotQueue<T>>);
+
+impl<T> SnapshotSender<T> {
+    /// Queues the snapshot and returns false once the receiver has been dropped.
+    pub(crate) fn send(&self, tag: VersionTag, snapshot: Arc<T>) -> bool {
+        if Arc::strong_count(&self.0) == 1 {
+            return false;
+        }
+
+        self.0.push(tag, snapshot);
+        true
+    }
+}
+
+impl<T> Drop for SnapshotSender<T> {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+/// The snapshots waiting for a `SnapshotReceiver`, keeping the `capacity` latest ones.
+struct SnapshotQueue<T> {
+    capacity: usize,
+    state: Mutex<SnapshotQueueState<T>>,
+}
+
+impl<T> SnapshotQueue<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(SnapshotQueueState {
+                closed: false,
+                snapshots: VecDeque::new(),
+                task: None,
+            }),
+        }
+    }
+
+    fn push(&self, tag: VersionTag, snapshot: Arc<T>) {
+        let mut state = self.state.lock().expect("SnapshotQueue");
+
+        if state.snapshots.len() == self.capacity {
+            state.snapshots.pop_front();
+        }
+
+        state.snapshots.push_back((tag, snapshot));
+        state.notify();
+    }
+
+    /// Ends the stream once the pending snapshots are consumed.
+    fn close(&self) {
+        let mut state = self.state.lock().expect("SnapshotQueue");
+        state.closed = true;
+        state.notify();
+    }
+}
+
+struct SnapshotQueueState<T> {
+    closed: bool,
+    snapshots: VecDeque<(VersionTag, Arc<T>)>,
+    task: Option<Task>,
+}
+
+impl<T> SnapshotQueueState<T> {
+    fn notify(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.notify();
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{RwLock, RwLockReadInitFut, Tagged};
+use futures::IntoFuture;
+use std::sync::OnceLock;
+
+/// A tagged `RwLock` that can be declared as a `static`.
+///
+/// The lock is created on first use and its value is initialized by the first call to
+/// `get_or_init`, which makes it suitable for global caches.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{StaticTagLock, Tagged};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// static CONFIG: StaticTagLock<String> = StaticTagLock::new();
+///
+/// let init = || Ok::<_, ()>(Tagged::new("config".to_string()));
+/// let len = block_on_all(CONFIG.get_or_init(init).map(|c| c.len())).unwrap();
+///
+/// assert_eq!(6, len);
+/// ```
+pub struct StaticTagLock<T> {
+    cell: OnceLock<RwLock<Option<Tagged<T>>>>,
+}
+
+impl<T> StaticTagLock<T> {
+    pub const fn new() -> Self {
+        Self {
+            cell: OnceLock::new(),
+        }
+    }
+
+    /// Returns the underlying lock, creating it on first use.
+    pub fn lock(&self) -> &RwLock<Option<Tagged<T>>> {
+        self.cell.get_or_init(RwLock::default)
+    }
+
+    /// Acquire the lock in read-only, initializing the value with `init` if missing.
+    ///
+    /// See `RwLock::read_or_init`.
+    #[cfg_attr(feature = "debug-tracking", track_caller)]
+    pub fn get_or_init<F, FUT>(&self, init: F) -> RwLockReadInitFut<F, FUT>
+    where
+        F: Fn() -> FUT,
+        FUT: IntoFuture<Item = Tagged<T>>,
+    {
+        self.lock().read_or_init(init)
+    }
+}
+
+impl<T> Default for StaticTagLock<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{RwLock, RwLockWriteFut, RwLockWriteGuard, SetTag};
+use futures::{Async, Future, Poll};
+use std::mem::{replace, swap};
+
+/// A `Future` swapping the values of two locks.
+///
+/// See `RwLock::swap_with`.
+pub struct SwapFut<T: SetTag> {
+    pub(crate) state: SwapState<T>,
+}
+
+pub(crate) enum SwapState<T: SetTag> {
+    Done,
+    First(RwLockWriteFut<T>, RwLock<T>),
+    Second(RwLockWriteGuard<T>, RwLockWriteFut<T>),
+}
+
+impl<T: SetTag> Future for SwapFut<T> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            match replace(&mut self.state, SwapState::Done) {
+                SwapState::Done => return Ok(Async::Ready(())),
+                SwapState::First(mut f, second) => match f.poll()? {
+                    Async::Ready(first) => self.state = SwapState::Second(first, second.write()),
+                    Async::NotReady => {
+                        self.state = SwapState::First(f, second);
+                        return Ok(Async::NotReady);
+                    }
+                },
+                SwapState::Second(mut first, mut f) => match f.poll()? {
+                    Async::Ready(mut second) => {
+                        swap(&mut *first, &mut *second);
+                        return Ok(Async::Ready(()));
+                    }
+                    Async::NotReady => {
+                        self.state = SwapState::Second(first, f);
+                        return Ok(Async::NotReady);
+                    }
+                },
+            }
+        }
+    }
+}
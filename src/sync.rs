@@ -0,0 +1,26 @@
+//! Synchronization primitives used by the internals of the crate.
+//!
+//! Under `cfg(loom)`, they are replaced by the loom equivalents so the internal state
+//! machines can be model-checked. The `Arc` handles exposed in the public API always
+//! come from `std`. The model checks live in `tests/loom.rs` and run with:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//! ```
+
+#[cfg(loom)]
+#[allow(unused_imports)]
+pub(crate) use loom::hint::spin_loop;
+#[cfg(loom)]
+#[allow(unused_imports)]
+pub(crate) use loom::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(loom)]
+pub(crate) use loom::sync::{Mutex, RwLock};
+
+#[cfg(not(loom))]
+pub(crate) use std::hint::spin_loop;
+#[cfg(not(loom))]
+#[allow(unused_imports)]
+pub(crate) use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(not(loom))]
+pub(crate) use std::sync::{Mutex, RwLock};
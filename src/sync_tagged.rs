@@ -0,0 +1,114 @@
+use crate::{RwLock, RwLockWriteFut, RwLockWriteGuard, Tagged};
+use futures::{Async, Future, Poll};
+use futures_locks::{RwLockReadFut, RwLockReadGuard};
+use std::mem::replace;
+
+/// Copies the value of `from` into `to` when `to` is stale, carrying over the tag of
+/// `from`.
+///
+/// The locks are acquired in the order of their `LockId` so opposite syncs cannot
+/// deadlock. Resolves to false if `to` was not stale.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{sync, RwLock};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let primary = RwLock::new_tagged(10);
+/// let replica = RwLock::new_tagged(0);
+///
+/// assert!(block_on_all(sync(&primary, &replica)).unwrap());
+/// assert!(!block_on_all(sync(&primary, &replica)).unwrap());
+///
+/// let tag = block_on_all(primary.read().map(|v| v.tag())).unwrap();
+/// assert_eq!(tag, block_on_all(replica.read().map(|v| v.tag())).unwrap());
+/// ```
+#[cfg_attr(feature = "debug-tracking", track_caller)]
+pub fn sync<T: Clone>(from: &RwLock<Tagged<T>>, to: &RwLock<Tagged<T>>) -> SyncFut<T> {
+    let state = if from.id() == to.id() {
+        SyncState::Done
+    } else if from.id() < to.id() {
+        SyncState::ReadFirst(from.read(), to.clone())
+    } else {
+        SyncState::WriteFirst(to.write(), from.clone())
+    };
+
+    SyncFut { state }
+}
+
+/// A `Future` copying the value of a lock into another one.
+///
+/// See `sync`.
+pub struct SyncFut<T: Clone> {
+    state: SyncState<T>,
+}
+
+enum SyncState<T: Clone> {
+    Done,
+    ReadFirst(RwLockReadFut<Tagged<T>>, RwLock<Tagged<T>>),
+    ReadSecond(RwLockWriteGuard<Tagged<T>>, RwLockReadFut<Tagged<T>>),
+    WriteFirst(RwLockWriteFut<Tagged<T>>, RwLock<Tagged<T>>),
+    WriteSecond(RwLockReadGuard<Tagged<T>>, RwLockWriteFut<Tagged<T>>),
+}
+
+impl<T: Clone> Future for SyncFut<T> {
+    type Item = bool;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let (from, to) = match replace(&mut self.state, SyncState::Done) {
+                SyncState::Done => return Ok(Async::Ready(false)),
+                SyncState::ReadFirst(mut f, to) => match f.poll()? {
+                    Async::Ready(from) => {
+                        self.state = SyncState::WriteSecond(from, to.write());
+                        continue;
+                    }
+                    Async::NotReady => {
+                        self.state = SyncState::ReadFirst(f, to);
+                        return Ok(Async::NotReady);
+                    }
+                },
+                SyncState::WriteFirst(mut f, from) => match f.poll()? {
+                    Async::Ready(to) => {
+                        self.state = SyncState::ReadSecond(to, from.read());
+                        continue;
+                    }
+                    Async::NotReady => {
+                        self.state = SyncState::WriteFirst(f, from);
+                        return Ok(Async::NotReady);
+                    }
+                },
+                SyncState::ReadSecond(to, mut f) => match f.poll()? {
+                    Async::Ready(from) => (from, to),
+                    Async::NotReady => {
+                        self.state = SyncState::ReadSecond(to, f);
+                        return Ok(Async::NotReady);
+                    }
+                },
+                SyncState::WriteSecond(from, mut f) => match f.poll()? {
+                    Async::Ready(to) => (from, to),
+                    Async::NotReady => {
+                        self.state = SyncState::WriteSecond(from, f);
+                        return Ok(Async::NotReady);
+                    }
+                },
+            };
+
+            return Ok(Async::Ready(copy(&from, to)));
+        }
+    }
+}
+
+fn copy<T: Clone>(from: &Tagged<T>, mut to: RwLockWriteGuard<Tagged<T>>) -> bool {
+    if to.tag() == from.tag() {
+        to.discard();
+        return false;
+    }
+
+    **to = (**from).clone();
+    to.set_new_tag(from.tag());
+    true
+}
@@ -0,0 +1,22 @@
+use crate::ChangeEvent;
+use version_tag::VersionTag;
+
+/// An observer notified of the tags committed on a `RwLock`.
+///
+/// Observers are registered with `RwLock::observe` and removed with `RwLock::unobserve`,
+/// which lets long-lived components subscribe and unsubscribe dynamically.
+pub trait TagObserver: Send + Sync {
+    /// Called inside the critical section of a write, after the new tag has been applied.
+    ///
+    /// On a coalescing lock, called once at the end of a burst with the latest tag instead,
+    /// outside of any critical section. See `RwLock::with_coalescing`.
+    fn on_tag(&self, tag: VersionTag);
+
+    /// Called instead of `on_tag` with the whole `ChangeEvent` of the write, carrying its
+    /// actor, label and reason.
+    ///
+    /// The default implementation calls `on_tag` with the tag of the event.
+    fn on_change(&self, event: &ChangeEvent) {
+        self.on_tag(event.tag);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::SetTag;
+use std::ops::{Deref, DerefMut};
+use version_tag::VersionTag;
+
+/// An adapter applying a new tag to the value of a foreign write guard when dropped.
+///
+/// Reuses the tagging discipline of this crate with locks it does not own.
+///
+/// # Example
+///
+/// ```
+/// use futures_tag_locks::{TagOnDrop, Tagged};
+/// use std::sync::Mutex;
+///
+/// let mutex = Mutex::new(Tagged::new(10));
+/// let before = mutex.lock().unwrap().tag();
+///
+/// let tag = {
+///     let mut guard = TagOnDrop::new(mutex.lock().unwrap());
+///     **guard = 12;
+///     guard.new_tag()
+/// };
+///
+/// assert_ne!(before, tag);
+/// assert_eq!(tag, mutex.lock().unwrap().tag());
+/// ```
+pub struct TagOnDrop<G>
+where
+    G: DerefMut,
+    G::Target: SetTag,
+{
+    guard: G,
+    new_tag: VersionTag,
+}
+
+impl<G> TagOnDrop<G>
+where
+    G: DerefMut,
+    G::Target: SetTag,
+{
+    pub fn new(guard: G) -> Self {
+        Self {
+            guard,
+            new_tag: VersionTag::new(),
+        }
+    }
+
+    /// Returns the tag applied when the adapter is dropped.
+    pub fn new_tag(&self) -> VersionTag {
+        self.new_tag
+    }
+}
+
+impl<G> Deref for TagOnDrop<G>
+where
+    G: DerefMut,
+    G::Target: SetTag,
+{
+    type Target = G::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<G> DerefMut for TagOnDrop<G>
+where
+    G: DerefMut,
+    G::Target: SetTag,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<G> Drop for TagOnDrop<G>
+where
+    G: DerefMut,
+    G::Target: SetTag,
+{
+    fn drop(&mut self) {
+        self.guard.set_tag(self.new_tag);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::sync::Mutex;
+use crate::{Clock, Debounce};
+use futures::task::{self, Task};
+use futures::{Async, Poll, Stream};
+use std::sync::Arc;
+use std::time::Duration;
+use version_tag::VersionTag;
+
+/// A `Stream` of the tags committed on a `RwLock`.
+///
+/// The stream conflates: when the consumer is slow, only the latest tag is yielded and
+/// the intermediate versions are skipped, much like a watch channel.
+/// The stream ends when all the handles on the `RwLock` have been dropped.
+pub struct TagStream {
+    clock: Arc<dyn Clock>,
+    version: usize,
+    watch: Arc<TagWatch>,
+}
+
+impl TagStream {
+    /// Yields at most one tag per `window`, the latest one, so a consumer such as a UI
+    /// repaints at a bounded rate even if the lock is written continuously.
+    ///
+    /// The window is measured and waited on with the clock of the lock, see `Clock`; the
+    /// system clock wakes the stream from the shared timer thread of the crate.
+    ///
+    /// ```
+    /// use futures::{Future, Stream};
+    /// use futures_tag_locks::RwLock;
+    /// use std::time::Duration;
+    ///
+    /// let lock = RwLock::new_tagged(0);
+    /// let changes = lock.subscribe().debounce(Duration::from_millis(20));
+    ///
+    /// let tag = lock.write().map(|mut w| { **w = 1; w.new_tag() }).wait().unwrap();
+    /// let (first, _) = changes.into_future().wait().ok().unwrap();
+    ///
+    /// assert_eq!(Some(tag), first);
+    /// ```
+    pub fn debounce(self, window: Duration) -> Debounce {
+        let clock = self.clock.clone();
+        Debounce::new(self, clock, window)
+    }
+}
+
+impl Stream for TagStream {
+    type Item = VersionTag;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let mut state = self.watch.state.lock().expect("TagStream");
+
+        if state.version != self.version {
+            self.version = state.version;
+            return Ok(Async::Ready(state.tag));
+        }
+
+        if state.closed {
+            return Ok(Async::Ready(None));
+        }
+
+        if !state.tasks.iter().any(Task::will_notify_current) {
+            state.tasks.push(task::current());
+        }
+
+        Ok(Async::NotReady)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TagWatch {
+    state: Mutex<WatchState>,
+}
+
+impl TagWatch {
+    pub(crate) fn subscribe(watch: &Arc<Self>, clock: Arc<dyn Clock>) -> TagStream {
+        TagStream {
+            clock,
+            version: watch.state.lock().expect("TagWatch").version,
+            watch: watch.clone(),
+        }
+    }
+
+    pub(crate) fn tag(&self) -> Option<VersionTag> {
+        self.state.lock().expect("TagWatch").tag
+    }
+
+    /// Publishes the tag and returns how many streams were woken.
+    pub(crate) fn publish(&self, tag: VersionTag) -> usize {
+        self.set(tag);
+        self.wake()
+    }
+
+    /// Records the tag without waking the streams, see `wake`.
+    pub(crate) fn set(&self, tag: VersionTag) {
+        self.state.lock().expect("TagWatch").tag = Some(tag);
+    }
+
+    /// Makes the streams yield the current tag and returns how many were woken.
+    pub(crate) fn wake(&self) -> usize {
+        let mut state = self.state.lock().expect("TagWatch");
+        let woken = state.tasks.len();
+
+        state.version = state.version.wrapping_add(1);
+        state.tasks.drain(..).for_each(|t| t.notify());
+        woken
+    }
+
+    pub(crate) fn close(&self) {
+        let mut state = self.state.lock().expect("TagWatch");
+
+        state.closed = true;
+        state.tasks.drain(..).for_each(|t| t.notify());
+    }
+}
+
+#[derive(Default)]
+struct WatchState {
+    closed: bool,
+    tag: Option<VersionTag>,
+    tasks: Vec<Task>,
+    version: usize,
+}
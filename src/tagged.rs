@@ -29,6 +29,20 @@ use version_tag::VersionTag;
 ///     let _ = w.new_tag();
 /// })).unwrap();
 /// ```
+///
+/// An unsized value, such as a slice, is stored behind a `Box`:
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{RwLock, Tagged};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let value: Box<Tagged<[u8]>> = Box::new(Tagged::new([1, 2, 3]));
+/// let lock = RwLock::new(value);
+///
+/// block_on_all(lock.write().map(|mut w| w[0] = 4)).unwrap();
+/// ```
+#[derive(Clone)]
 pub struct Tagged<T: ?Sized> {
     tag: VersionTag,
     value: T,
@@ -41,12 +55,20 @@ impl<T> Tagged<T> {
             value,
         }
     }
+}
 
+impl<T: ?Sized> Tagged<T> {
     pub fn tag(&self) -> VersionTag {
         self.tag
     }
 }
 
+impl<T: Default> Default for Tagged<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
 impl<T: ?Sized> Deref for Tagged<T> {
     type Target = T;
 
@@ -0,0 +1,107 @@
+use crate::sync::{spin_loop, AtomicUsize, Mutex, Ordering, RwLock};
+use std::sync::{PoisonError, TryLockError};
+use version_tag::VersionTag;
+
+/// A `Copy` value with its tag, loaded and stored without any future.
+///
+/// Despite its name, the value is kept in two lock-protected slots, so a `load` never
+/// waits on a `store`. A panic in `update` abandons the store without poisoning.
+///
+/// # Example
+///
+/// ```
+/// use futures_tag_locks::TaggedAtomic;
+///
+/// let enabled = TaggedAtomic::new(false);
+/// let (_, before) = enabled.load();
+/// let tag = enabled.store(true);
+///
+/// assert_ne!(before, tag);
+/// assert_eq!((true, tag), enabled.load());
+/// ```
+pub struct TaggedAtomic<T> {
+    seq: AtomicUsize,
+    slots: [RwLock<(T, VersionTag)>; 2],
+    writer: Mutex<()>,
+}
+
+impl<T: Copy> TaggedAtomic<T> {
+    pub fn new(value: T) -> Self {
+        let tag = VersionTag::new();
+
+        Self {
+            seq: AtomicUsize::new(0),
+            slots: [RwLock::new((value, tag)), RwLock::new((value, tag))],
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Returns the value along with the tag of the store that produced it.
+    pub fn load(&self) -> (T, VersionTag) {
+        loop {
+            let seq = self.seq.load(Ordering::SeqCst);
+
+            let slot = match self.slots[seq & 1].try_read() {
+                Ok(slot) => Some(slot),
+                Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+                Err(TryLockError::WouldBlock) => None,
+            };
+
+            if let Some(slot) = slot {
+                let value = *slot;
+                drop(slot);
+
+                if self.seq.load(Ordering::SeqCst) == seq {
+                    return value;
+                }
+            }
+
+            spin_loop();
+        }
+    }
+
+    pub fn get(&self) -> T {
+        self.load().0
+    }
+
+    pub fn tag(&self) -> VersionTag {
+        self.load().1
+    }
+
+    /// Stores `value` and returns the new tag.
+    pub fn store(&self, value: T) -> VersionTag {
+        self.update(|v| *v = value)
+    }
+
+    /// Updates the value with `f` and returns the new tag.
+    ///
+    /// The updates are applied one at a time, so `f` always sees the value of the
+    /// previous store.
+    pub fn update<F>(&self, f: F) -> VersionTag
+    where
+        F: FnOnce(&mut T),
+    {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let seq = self.seq.load(Ordering::SeqCst);
+        let (mut value, _) = *self.slots[seq & 1]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        f(&mut value);
+
+        let tag = VersionTag::new();
+
+        *self.slots[seq.wrapping_add(1) & 1]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = (value, tag);
+
+        self.seq.store(seq.wrapping_add(1), Ordering::SeqCst);
+        tag
+    }
+}
+
+impl<T: Copy + Default> Default for TaggedAtomic<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
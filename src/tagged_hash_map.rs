@@ -0,0 +1,224 @@
+use crate::SetTag;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use version_tag::VersionTag;
+
+/// A hash map tracking a version tag per key in addition to the collection tag.
+///
+/// `changed_keys` lists the keys that moved since a tag, falling back to all the keys
+/// for tags older than the last of the `MAX_REMOVED_KEYS` removals remembered.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{RwLock, TaggedHashMap};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let lock = RwLock::new(TaggedHashMap::new());
+///
+/// block_on_all(lock.write().map(|mut w| {
+///     w.insert("a", 1);
+///     w.insert("b", 2);
+/// })).unwrap();
+///
+/// let seen = block_on_all(lock.read().map(|m| m.tag())).unwrap();
+///
+/// block_on_all(lock.write().map(|mut w| {
+///     w.remove("a");
+/// })).unwrap();
+///
+/// block_on_all(lock.read().map(move |m| {
+///     assert_eq!(vec![&"a"], m.changed_keys(seen));
+/// })).unwrap();
+/// ```
+pub struct TaggedHashMap<K, V> {
+    dirty: Vec<K>,
+    entries: HashMap<K, Entry<V>>,
+    pruned: u64,
+    removed: HashMap<K, u64>,
+    seq: u64,
+    tag: VersionTag,
+}
+
+/// The number of removed keys remembered by a `TaggedHashMap`.
+pub const MAX_REMOVED_KEYS: usize = 1024;
+
+struct Entry<V> {
+    seq: u64,
+    tag: VersionTag,
+    value: V,
+}
+
+impl<K: Clone + Eq + Hash, V> TaggedHashMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            dirty: Vec::new(),
+            entries: HashMap::new(),
+            pruned: 0,
+            removed: HashMap::new(),
+            seq: 0,
+            tag: VersionTag::new(),
+        }
+    }
+
+    /// Returns the tag of the collection, updated by any write.
+    pub fn tag(&self) -> VersionTag {
+        self.tag
+    }
+
+    /// Returns the tag of the value of `key`, or `None` if the key is missing.
+    pub fn tag_of<Q>(&self, key: &Q) -> Option<VersionTag>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.entries.get(key).map(|e| e.tag)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.entries.get(key).map(|e| &e.value)
+    }
+
+    /// Returns a mutable reference to the value of `key`, marking it as changed.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (k, _) = self.entries.get_key_value(key)?;
+        self.dirty.push(k.clone());
+        self.entries.get_mut(key).map(|e| &mut e.value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.entries.contains_key(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.dirty.push(key.clone());
+
+        let entry = Entry {
+            seq: self.seq,
+            tag: self.tag,
+            value,
+        };
+
+        self.entries.insert(key, entry).map(|e| e.value)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (key, entry) = self.entries.remove_entry(key)?;
+        self.dirty.push(key);
+        Some(entry.value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(k, e)| (k, &e.value))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.keys()
+    }
+
+    /// Returns the keys inserted, updated or removed by the writes committed after the
+    /// write that produced `since`.
+    ///
+    /// If `since` is not known to the map anymore, or is older than the removals it
+    /// forgot, all the present and removed keys are returned.
+    pub fn changed_keys(&self, since: VersionTag) -> Vec<&K> {
+        let seq = self.seq_of(since).filter(|seq| *seq >= self.pruned);
+        let moved = |s: u64| match seq {
+            Some(seq) => s > seq,
+            None => true,
+        };
+
+        self.entries
+            .iter()
+            .filter(|(_, e)| moved(e.seq))
+            .map(|(k, _)| k)
+            .chain(
+                self.removed
+                    .iter()
+                    .filter(|(_, s)| moved(**s))
+                    .map(|(k, _)| k),
+            )
+            .collect()
+    }
+
+    fn seq_of(&self, tag: VersionTag) -> Option<u64> {
+        if tag == self.tag {
+            return Some(self.seq);
+        }
+
+        self.entries.values().find(|e| e.tag == tag).map(|e| e.seq)
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> Default for TaggedHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> SetTag for TaggedHashMap<K, V> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        self.seq += 1;
+
+        for key in self.dirty.drain(..) {
+            match self.entries.get_mut(&key) {
+                Some(entry) => {
+                    entry.seq = self.seq;
+                    entry.tag = tag;
+                    self.removed.remove(&key);
+                }
+                None => {
+                    self.removed.insert(key, self.seq);
+                }
+            }
+        }
+
+        if self.removed.len() > MAX_REMOVED_KEYS {
+            self.prune_removed();
+        }
+
+        self.tag = tag;
+    }
+
+    fn discard_changes(&mut self) {
+        self.dirty.clear();
+    }
+}
+
+impl<K: Eq + Hash, V> TaggedHashMap<K, V> {
+    /// Forgets the oldest half of the removed keys.
+    fn prune_removed(&mut self) {
+        let mut seqs: Vec<u64> = self.removed.values().copied().collect();
+        let (_, cutoff, _) = seqs.select_nth_unstable(self.removed.len() / 2);
+        let cutoff = *cutoff;
+
+        self.removed.retain(|_, s| *s > cutoff);
+        self.pruned = self.pruned.max(cutoff);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::SetTag;
+use std::ops::{Deref, DerefMut, Range};
+use version_tag::VersionTag;
+
+/// A wrapper keeping a version tag per fixed-size region of a large value.
+///
+/// The regions overlapping the ranges marked with `mark_region` take the new tag on
+/// commit; unmarked changes only move the tag of the whole value.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{RwLock, TaggedRegions};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let lock = RwLock::new(TaggedRegions::new(vec![0u8; 4096], 1024));
+/// let before = block_on_all(lock.read().map(|d| d.region_tag(0))).unwrap();
+///
+/// block_on_all(lock.write().map(|mut w| {
+///     w[2048..2100].copy_from_slice(&[1; 52]);
+///     w.mark_region(2048..2100);
+/// })).unwrap();
+///
+/// block_on_all(lock.read().map(move |d| {
+///     assert_eq!(before, d.region_tag(0));
+///     assert_eq!(d.tag(), d.region_tag(2));
+/// })).unwrap();
+/// ```
+pub struct TaggedRegions<T: ?Sized> {
+    all: bool,
+    base: VersionTag,
+    dirty: Vec<Range<usize>>,
+    region_len: usize,
+    tag: VersionTag,
+    tags: Vec<VersionTag>,
+    value: T,
+}
+
+impl<T> TaggedRegions<T> {
+    /// Wraps `value`, tracking its tags per region of `region_len` units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `region_len` is zero.
+    pub fn new(value: T, region_len: usize) -> Self {
+        assert!(region_len > 0, "region_len must not be zero");
+
+        let tag = VersionTag::new();
+
+        Self {
+            all: false,
+            base: tag,
+            dirty: Vec::new(),
+            region_len,
+            tag,
+            tags: Vec::new(),
+            value,
+        }
+    }
+}
+
+impl<T: ?Sized> TaggedRegions<T> {
+    /// Returns the tag of the whole value, updated by any write.
+    pub fn tag(&self) -> VersionTag {
+        self.tag
+    }
+
+    /// Returns the tag of the region at index `region`.
+    pub fn region_tag(&self, region: usize) -> VersionTag {
+        self.tags.get(region).copied().unwrap_or(self.base)
+    }
+
+    /// Returns the tag of the region containing the unit at `pos`.
+    pub fn region_tag_at(&self, pos: usize) -> VersionTag {
+        self.region_tag(pos / self.region_len)
+    }
+
+    pub fn region_len(&self) -> usize {
+        self.region_len
+    }
+
+    /// Marks the units in `range` as modified by the current write.
+    pub fn mark_region(&mut self, range: Range<usize>) {
+        if !range.is_empty() {
+            self.dirty.push(range);
+        }
+    }
+
+    /// Marks the whole value as modified by the current write.
+    pub fn mark_all(&mut self) {
+        self.all = true;
+    }
+}
+
+impl<T: ?Sized> Deref for TaggedRegions<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: ?Sized> DerefMut for TaggedRegions<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: ?Sized> SetTag for TaggedRegions<T> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        if self.all {
+            self.all = false;
+            self.base = tag;
+            self.dirty.clear();
+            self.tags.clear();
+        }
+
+        for range in self.dirty.drain(..) {
+            let first = range.start / self.region_len;
+            let last = (range.end - 1) / self.region_len;
+
+            if self.tags.len() <= last {
+                self.tags.resize(last + 1, self.base);
+            }
+
+            self.tags[first..=last].iter_mut().for_each(|t| *t = tag);
+        }
+
+        self.tag = tag;
+    }
+
+    fn discard_changes(&mut self) {
+        self.all = false;
+        self.dirty.clear();
+    }
+}
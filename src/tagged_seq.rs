@@ -0,0 +1,97 @@
+use crate::TaggedAtomic;
+use futures::{try_ready, Async, Future, Poll};
+use futures_locks::{Mutex, MutexFut};
+use std::sync::Arc;
+use version_tag::VersionTag;
+
+/// A small `Copy` value with its tag, read without ever waiting on the writers.
+///
+/// The value is kept in a `TaggedAtomic` and the writers wait their turn asynchronously,
+/// for tiny hot values like counters.
+///
+/// # Example
+///
+/// ```
+/// use futures_tag_locks::TaggedSeq;
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let stats = TaggedSeq::new((0u32, 0u64));
+/// let tag = block_on_all(stats.write(|s| *s = (s.0 + 1, s.1 + 512))).unwrap();
+///
+/// assert_eq!(((1, 512), tag), stats.load());
+/// ```
+pub struct TaggedSeq<T>(Arc<SeqInner<T>>);
+
+struct SeqInner<T> {
+    cell: TaggedAtomic<T>,
+    writer: Mutex<()>,
+}
+
+impl<T: Copy> TaggedSeq<T> {
+    pub fn new(value: T) -> Self {
+        TaggedSeq(Arc::new(SeqInner {
+            cell: TaggedAtomic::new(value),
+            writer: Mutex::new(()),
+        }))
+    }
+
+    /// Returns the value along with the tag of the write that produced it.
+    pub fn load(&self) -> (T, VersionTag) {
+        self.0.cell.load()
+    }
+
+    pub fn get(&self) -> T {
+        self.load().0
+    }
+
+    pub fn tag(&self) -> VersionTag {
+        self.load().1
+    }
+
+    /// Updates the value with `f` and resolves with the new tag.
+    ///
+    /// The writers are applied one at a time, in the order they acquire the critical
+    /// section. The readers see the new value once the `Future` resolves.
+    pub fn write<F>(&self, f: F) -> TaggedSeqWriteFut<T, F>
+    where
+        F: FnOnce(&mut T),
+    {
+        TaggedSeqWriteFut {
+            f: Some(f),
+            fut: self.0.writer.lock(),
+            inner: self.0.clone(),
+        }
+    }
+}
+
+impl<T> Clone for TaggedSeq<T> {
+    fn clone(&self) -> Self {
+        TaggedSeq(self.0.clone())
+    }
+}
+
+/// A `Future` applying a write on a `TaggedSeq`.
+///
+/// See `TaggedSeq::write`.
+pub struct TaggedSeqWriteFut<T, F> {
+    f: Option<F>,
+    fut: MutexFut<()>,
+    inner: Arc<SeqInner<T>>,
+}
+
+impl<T, F> Future for TaggedSeqWriteFut<T, F>
+where
+    T: Copy,
+    F: FnOnce(&mut T),
+{
+    type Item = VersionTag;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let _guard = try_ready!(self.fut.poll());
+        let f = self.f.take().expect("Cannot poll twice");
+        let tag = self.inner.cell.update(f);
+
+        Ok(Async::Ready(tag))
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{RwLock, RwLockWriteFut, Tagged};
+use futures::{try_ready, Async, AsyncSink, Future, Poll, Sink, StartSend};
+
+/// A `Sink` writing each item it receives into a `RwLock<Tagged<T>>`, bumping the tag.
+///
+/// See `RwLock::sink`.
+pub struct TaggedSink<T> {
+    lock: RwLock<Tagged<T>>,
+    pending: Option<(RwLockWriteFut<Tagged<T>>, T)>,
+}
+
+impl<T> TaggedSink<T> {
+    pub(crate) fn new(lock: RwLock<Tagged<T>>) -> Self {
+        Self {
+            lock,
+            pending: None,
+        }
+    }
+}
+
+impl<T> Sink for TaggedSink<T> {
+    type SinkItem = T;
+    type SinkError = ();
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        if self.poll_complete()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(item));
+        }
+
+        self.pending = Some((self.lock.write(), item));
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        if let Some((fut, _)) = &mut self.pending {
+            let mut guard = try_ready!(fut.poll());
+            let (_, value) = self.pending.take().expect("TaggedSink");
+
+            **guard = value;
+        }
+
+        Ok(Async::Ready(()))
+    }
+}
@@ -0,0 +1,142 @@
+use crate::SetTag;
+use version_tag::VersionTag;
+
+/// An arena of values with stable keys, tracking a version tag per slot.
+///
+/// The slots touched by a write take its tag on commit. A removed key may be reused.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{RwLock, TaggedSlab};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let lock = RwLock::new(TaggedSlab::new());
+/// let (a, b) = block_on_all(lock.write().map(|mut w| (w.insert(1), w.insert(2)))).unwrap();
+/// let before = block_on_all(lock.read().map(move |s| s.tag_of(a))).unwrap();
+///
+/// block_on_all(lock.write().map(move |mut w| *w.get_mut(b).unwrap() = 3)).unwrap();
+///
+/// block_on_all(lock.read().map(move |s| {
+///     assert_eq!(before, s.tag_of(a));
+///     assert_eq!(Some(&3), s.get(b));
+/// })).unwrap();
+/// ```
+pub struct TaggedSlab<T> {
+    dirty: Vec<usize>,
+    free: Vec<usize>,
+    len: usize,
+    slots: Vec<Slot<T>>,
+    tag: VersionTag,
+}
+
+struct Slot<T> {
+    tag: VersionTag,
+    value: Option<T>,
+}
+
+impl<T> TaggedSlab<T> {
+    pub fn new() -> Self {
+        Self {
+            dirty: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            slots: Vec::new(),
+            tag: VersionTag::new(),
+        }
+    }
+
+    /// Returns the tag of the arena, updated by any write.
+    pub fn tag(&self) -> VersionTag {
+        self.tag
+    }
+
+    /// Returns the tag of the value at `key`, or `None` if the slot is empty.
+    pub fn tag_of(&self, key: usize) -> Option<VersionTag> {
+        self.slots
+            .get(key)
+            .filter(|s| s.value.is_some())
+            .map(|s| s.tag)
+    }
+
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.slots.get(key)?.value.as_ref()
+    }
+
+    /// Returns a mutable reference to the value at `key`, marking it as changed.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        let value = self.slots.get_mut(key)?.value.as_mut()?;
+        self.dirty.push(key);
+        Some(value)
+    }
+
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Stores `value` in a free slot and returns its key.
+    pub fn insert(&mut self, value: T) -> usize {
+        let key = match self.free.pop() {
+            Some(key) => {
+                self.slots[key].value = Some(value);
+                key
+            }
+            None => {
+                self.slots.push(Slot {
+                    tag: self.tag,
+                    value: Some(value),
+                });
+                self.slots.len() - 1
+            }
+        };
+
+        self.dirty.push(key);
+        self.len += 1;
+        key
+    }
+
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        let value = self.slots.get_mut(key)?.value.take()?;
+
+        self.dirty.push(key);
+        self.free.push(key);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over the keys and values of the occupied slots.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(k, s)| s.value.as_ref().map(|v| (k, v)))
+    }
+}
+
+impl<T> Default for TaggedSlab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SetTag for TaggedSlab<T> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        let slots = &mut self.slots;
+
+        self.dirty.drain(..).for_each(|k| slots[k].tag = tag);
+        self.tag = tag;
+    }
+
+    fn discard_changes(&mut self) {
+        self.dirty.clear();
+    }
+}
@@ -0,0 +1,147 @@
+use crate::SetTag;
+use std::ops::Deref;
+use version_tag::VersionTag;
+
+/// A vector tracking a version tag per element in addition to the collection tag.
+///
+/// The elements touched by a write, including those shifted by an insert or a remove,
+/// take its tag on commit.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::{RwLock, TaggedVec};
+/// use tokio::executor::current_thread::block_on_all;
+///
+/// let lock = RwLock::new(TaggedVec::from(vec![1, 2, 3]));
+/// let before = block_on_all(lock.read().map(|v| v.tag_at(0))).unwrap();
+///
+/// block_on_all(lock.write().map(|mut w| w.set(2, 4))).unwrap();
+///
+/// block_on_all(lock.read().map(move |v| {
+///     assert_eq!(before, v.tag_at(0));
+///     assert_eq!(Some(v.tag()), v.tag_at(2));
+/// })).unwrap();
+/// ```
+pub struct TaggedVec<T> {
+    dirty: Vec<usize>,
+    items: Vec<T>,
+    tag: VersionTag,
+    tags: Vec<VersionTag>,
+}
+
+impl<T> TaggedVec<T> {
+    pub fn new() -> Self {
+        Self::from(Vec::new())
+    }
+
+    /// Returns the tag of the collection, updated by any write.
+    pub fn tag(&self) -> VersionTag {
+        self.tag
+    }
+
+    /// Returns the tag of the element at `index`, or `None` if out of bounds.
+    pub fn tag_at(&self, index: usize) -> Option<VersionTag> {
+        self.tags.get(index).copied()
+    }
+
+    /// Returns a mutable reference to the element at `index`, marking it as changed.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let item = self.items.get_mut(index)?;
+        self.dirty.push(index);
+        Some(item)
+    }
+
+    /// Replaces the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        self.items[index] = value;
+        self.dirty.push(index);
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.dirty.push(self.items.len());
+        self.items.push(value);
+        self.tags.push(self.tag);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.tags.pop();
+        self.items.pop()
+    }
+
+    /// Inserts an element at `index`, shifting the following elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.items.insert(index, value);
+        self.tags.insert(index, self.tag);
+        self.dirty.extend(index..self.items.len());
+    }
+
+    /// Removes the element at `index`, shifting the following elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        self.tags.remove(index);
+        self.dirty.extend(index..self.tags.len());
+        self.items.remove(index)
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.tags.clear();
+    }
+}
+
+impl<T> Default for TaggedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Vec<T>> for TaggedVec<T> {
+    fn from(items: Vec<T>) -> Self {
+        let tag = VersionTag::new();
+
+        Self {
+            dirty: Vec::new(),
+            tags: vec![tag; items.len()],
+            items,
+            tag,
+        }
+    }
+}
+
+impl<T> Deref for TaggedVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl<T> SetTag for TaggedVec<T> {
+    fn set_tag(&mut self, tag: VersionTag) {
+        let tags = &mut self.tags;
+
+        self.dirty.drain(..).for_each(|i| {
+            if let Some(t) = tags.get_mut(i) {
+                *t = tag;
+            }
+        });
+        self.tag = tag;
+    }
+
+    fn discard_changes(&mut self) {
+        self.dirty.clear();
+    }
+}
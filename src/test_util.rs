@@ -0,0 +1,238 @@
+//! Helpers to test code built on this crate.
+//!
+//! Requires the `test-util` feature.
+
+use crate::rw_lock::Hook;
+use crate::{Clock, RwLock};
+use futures::executor::{self, Notify, NotifyHandle, Spawn};
+use futures::{Future, Poll};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant, SystemTime};
+use version_tag::VersionTag;
+
+/// Runs the future `f` to completion and panics if no write was committed on `lock`.
+pub fn assert_tag_changed<T, F: Future>(lock: &RwLock<T>, f: F) -> Result<F::Item, F::Error> {
+    let (count, result) = count_tags(lock, f);
+    assert!(count > 0, "the tag of the lock has not changed");
+    result
+}
+
+/// Runs the future `f` to completion and panics if a write was committed on `lock`.
+pub fn assert_tag_unchanged<T, F: Future>(lock: &RwLock<T>, f: F) -> Result<F::Item, F::Error> {
+    let (count, result) = count_tags(lock, f);
+    assert_eq!(0, count, "the tag of the lock has changed");
+    result
+}
+
+/// Counts the commits with a hook, so the observers delayed by `coalesce` or the
+/// streams delayed by `debounce` do not hide them.
+fn count_tags<T, F: Future>(lock: &RwLock<T>, f: F) -> (usize, Result<F::Item, F::Error>) {
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    lock.0.add_hook(on_commit(&counter, |counter, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    }));
+
+    let result = f.wait();
+    (counter.load(Ordering::SeqCst), result)
+}
+
+/// A hook calling `f` on each commit until `target` is dropped.
+fn on_commit<T: ?Sized, V: Send + Sync + 'static>(
+    target: &Arc<V>,
+    f: fn(&V, VersionTag),
+) -> Hook<T> {
+    let target: Weak<V> = Arc::downgrade(target);
+
+    Box::new(move |_, tag| match target.upgrade() {
+        Some(target) => {
+            f(&target, tag);
+            true
+        }
+        None => false,
+    })
+}
+
+/// Numbers the tags committed on a lock in order: 1 for the first commit after `new`,
+/// 2 for the next one, and so on.
+///
+/// The tags are recorded on commit, even on a lock delaying its notifications.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::test_util::TagSequence;
+/// use futures_tag_locks::RwLock;
+///
+/// let lock = RwLock::new_tagged(0);
+/// let sequence = TagSequence::new(&lock);
+///
+/// let first = lock.write().map(|mut w| { **w = 1; w.new_tag() }).wait().unwrap();
+/// let second = lock.write().map(|mut w| { **w = 2; w.new_tag() }).wait().unwrap();
+///
+/// assert_eq!(Some(1), sequence.ordinal(first));
+/// assert_eq!(Some(2), sequence.ordinal(second));
+/// assert_eq!(vec![first, second], sequence.tags());
+/// ```
+pub struct TagSequence(Arc<TagRecorder>);
+
+impl TagSequence {
+    /// Starts numbering the tags committed on `lock`.
+    pub fn new<T: ?Sized>(lock: &RwLock<T>) -> Self {
+        let recorder = Arc::new(TagRecorder::default());
+
+        lock.0.add_hook(on_commit(&recorder, |recorder, tag| {
+            recorder.tags.lock().expect("TagRecorder").push(tag);
+        }));
+
+        Self(recorder)
+    }
+
+    /// Returns the ordinal of `tag`, starting at 1, or `None` if it was not committed
+    /// on the lock since the sequence was created.
+    pub fn ordinal(&self, tag: VersionTag) -> Option<usize> {
+        self.tags().iter().position(|t| *t == tag).map(|i| i + 1)
+    }
+
+    /// Returns the tags committed on the lock, in order.
+    pub fn tags(&self) -> Vec<VersionTag> {
+        self.0.tags.lock().expect("TagSequence").clone()
+    }
+}
+
+#[derive(Default)]
+struct TagRecorder {
+    tags: Mutex<Vec<VersionTag>>,
+}
+
+/// A single-threaded harness polling a `Future` step by step.
+///
+/// # Example
+///
+/// ```
+/// use futures::Future;
+/// use futures_tag_locks::test_util::Harness;
+/// use futures_tag_locks::{RwLock, Tagged};
+///
+/// let lock = RwLock::new(Tagged::new(10));
+/// let write = lock.write().wait().unwrap();
+///
+/// let mut read = Harness::new(lock.read());
+/// assert!(read.poll().unwrap().is_not_ready());
+///
+/// drop(write);
+/// assert!(read.is_notified());
+/// assert!(read.poll().unwrap().is_ready());
+/// ```
+pub struct Harness<F> {
+    notify: Arc<Flag>,
+    spawn: Spawn<F>,
+}
+
+impl<F: Future> Harness<F> {
+    pub fn new(f: F) -> Self {
+        Self {
+            notify: Arc::new(Flag(AtomicBool::new(false))),
+            spawn: executor::spawn(f),
+        }
+    }
+
+    /// Polls the future once.
+    pub fn poll(&mut self) -> Poll<F::Item, F::Error> {
+        self.notify.0.store(false, Ordering::SeqCst);
+        self.spawn
+            .poll_future_notify(&NotifyHandle::from(self.notify.clone()), 0)
+    }
+
+    /// Returns true if the future has been woken up since the last poll.
+    pub fn is_notified(&self) -> bool {
+        self.notify.0.load(Ordering::SeqCst)
+    }
+}
+
+struct Flag(AtomicBool);
+
+impl Notify for Flag {
+    fn notify(&self, _: usize) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A `Clock` that only moves forward when told to.
+///
+/// The callbacks scheduled on the clock run inside `advance`, once their time is reached.
+pub struct MockClock {
+    now: Mutex<Instant>,
+    random: Mutex<f64>,
+    scheduled: Mutex<Vec<(Instant, Scheduled)>>,
+    start: (Instant, SystemTime),
+}
+
+type Scheduled = Box<dyn FnOnce() + Send>;
+
+impl MockClock {
+    pub fn new() -> Self {
+        let start = (Instant::now(), SystemTime::now());
+
+        Self {
+            now: Mutex::new(start.0),
+            random: Mutex::new(1.0),
+            scheduled: Default::default(),
+            start,
+        }
+    }
+
+    /// Sets the number returned by `Clock::random`, 1 by default, which never draws an
+    /// early refresh.
+    pub fn set_random(&self, random: f64) {
+        *self.random.lock().expect("MockClock") = random;
+    }
+
+    /// Moves the clock forward by `duration` and runs the callbacks that became due.
+    pub fn advance(&self, duration: Duration) {
+        let now = {
+            let mut now = self.now.lock().expect("MockClock");
+            *now += duration;
+            *now
+        };
+
+        let due = {
+            let mut scheduled = self.scheduled.lock().expect("MockClock");
+            let (due, later) = scheduled.drain(..).partition(|(at, _)| *at <= now);
+            *scheduled = later;
+            due
+        };
+
+        due.into_iter().for_each(|(_, f): (_, Scheduled)| f());
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().expect("MockClock")
+    }
+
+    fn system_now(&self) -> SystemTime {
+        self.start.1 + self.now().duration_since(self.start.0)
+    }
+
+    fn random(&self) -> f64 {
+        *self.random.lock().expect("MockClock")
+    }
+
+    fn schedule(&self, at: Instant, f: Box<dyn FnOnce() + Send>) {
+        if at <= self.now() {
+            f()
+        } else {
+            self.scheduled.lock().expect("MockClock").push((at, f));
+        }
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
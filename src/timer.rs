@@ -0,0 +1,96 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::Instant;
+
+/// The timer thread shared by all the `SystemClock`s, started on the first `schedule`.
+///
+/// The callbacks run one after the other on that thread, so they must be short.
+pub(crate) struct Timer {
+    state: Mutex<TimerState>,
+    wake: Condvar,
+}
+
+impl Timer {
+    pub(crate) fn global() -> &'static Timer {
+        static TIMER: OnceLock<Timer> = OnceLock::new();
+
+        TIMER.get_or_init(|| {
+            thread::Builder::new()
+                .name("futures_tag_locks-timer".into())
+                .spawn(|| Timer::global().run())
+                .expect("Timer");
+
+            Timer {
+                state: Default::default(),
+                wake: Condvar::new(),
+            }
+        })
+    }
+
+    pub(crate) fn schedule(&self, at: Instant, f: Box<dyn FnOnce() + Send>) {
+        let mut state = self.state.lock().expect("Timer");
+        let seq = state.next;
+
+        state.next += 1;
+        state.entries.push(Entry { at, f, seq });
+        self.wake.notify_one();
+    }
+
+    fn run(&self) {
+        let mut state = self.state.lock().expect("Timer");
+
+        loop {
+            let now = Instant::now();
+
+            state = match state.entries.peek() {
+                Some(entry) if entry.at <= now => {
+                    let entry = state.entries.pop().expect("Timer");
+
+                    drop(state);
+                    (entry.f)();
+                    self.state.lock().expect("Timer")
+                }
+                Some(entry) => {
+                    let delay = entry.at - now;
+                    self.wake.wait_timeout(state, delay).expect("Timer").0
+                }
+                None => self.wake.wait(state).expect("Timer"),
+            };
+        }
+    }
+}
+
+#[derive(Default)]
+struct TimerState {
+    entries: BinaryHeap<Entry>,
+    next: u64,
+}
+
+/// A scheduled callback, ordered so the earliest one is at the top of the heap.
+struct Entry {
+    at: Instant,
+    f: Box<dyn FnOnce() + Send>,
+    seq: u64,
+}
+
+impl Eq for Entry {}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.at, other.seq).cmp(&(self.at, self.seq))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,18 @@
+use futures::sync::mpsc::UnboundedReceiver;
+use futures::{Poll, Stream};
+use version_tag::VersionTag;
+
+/// A `Stream` of the tag and a clone of the value for every write committed on a
+/// `RwLock`.
+///
+/// See `RwLock::value_stream`.
+pub struct ValueStream<T>(pub(crate) UnboundedReceiver<(VersionTag, T)>);
+
+impl<T> Stream for ValueStream<T> {
+    type Item = (VersionTag, T);
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.0.poll()
+    }
+}
@@ -0,0 +1,24 @@
+use crate::rw_lock::Inner;
+use crate::RwLock;
+use std::sync::Weak;
+
+/// A weak handle on a `RwLock`.
+///
+/// Holding a `WeakRwLock` does not keep the protected data alive, which makes it
+/// suitable for caches and registries of locks that must not leak resources.
+pub struct WeakRwLock<T: ?Sized>(pub(crate) Weak<Inner<T>>);
+
+impl<T: ?Sized> WeakRwLock<T> {
+    /// Try to get back a `RwLock` handle.
+    ///
+    /// Returns `None` if all the `RwLock` handles have been dropped.
+    pub fn upgrade(&self) -> Option<RwLock<T>> {
+        self.0.upgrade().map(RwLock)
+    }
+}
+
+impl<T: ?Sized> Clone for WeakRwLock<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
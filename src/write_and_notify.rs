@@ -0,0 +1,47 @@
+use crate::{RwLockWriteFut, SetTag};
+use futures::{try_ready, Async, Future, Poll};
+use version_tag::VersionTag;
+
+/// What a commit notified, as reported by `RwLock::write_and_notify`.
+#[derive(Clone, Copy, Debug)]
+pub struct Notified {
+    /// The number of hooks run, including those backing the snapshot and value streams.
+    pub hooks: usize,
+
+    /// The number of observers notified.
+    ///
+    /// Zero on a coalescing lock, where the observers are notified at the end of the burst.
+    pub observers: usize,
+
+    /// The number of tag streams woken, zero on a coalescing lock.
+    pub streams: usize,
+
+    /// The tag committed by the write.
+    pub tag: VersionTag,
+}
+
+/// A `Future` applying a closure under the write lock and reporting the notifications
+/// of the commit.
+///
+/// See `RwLock::write_and_notify`.
+pub struct WriteAndNotifyFut<T: ?Sized + SetTag, F> {
+    pub(crate) f: Option<F>,
+    pub(crate) fut: RwLockWriteFut<T>,
+}
+
+impl<T, F> Future for WriteAndNotifyFut<T, F>
+where
+    T: ?Sized + SetTag,
+    F: FnOnce(&mut T),
+{
+    type Item = Notified;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let mut guard = try_ready!(self.fut.poll());
+        let f = self.f.take().expect("Cannot poll twice");
+
+        f(&mut guard);
+        Ok(Async::Ready(guard.finish_and_notify()))
+    }
+}
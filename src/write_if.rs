@@ -0,0 +1,32 @@
+use crate::{RwLockWriteFut, RwLockWriteGuard, SetTag};
+use futures::{try_ready, Async, Future, Poll};
+
+/// A `Future` acquiring the write lock and handing out the guard only if a predicate
+/// holds on the value.
+///
+/// See `RwLock::write_if`.
+pub struct RwLockWriteIfFut<T: ?Sized + SetTag, P> {
+    pub(crate) fut: RwLockWriteFut<T>,
+    pub(crate) predicate: Option<P>,
+}
+
+impl<T, P> Future for RwLockWriteIfFut<T, P>
+where
+    T: ?Sized + SetTag,
+    P: FnOnce(&T) -> bool,
+{
+    type Item = Option<RwLockWriteGuard<T>>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let guard = try_ready!(self.fut.poll());
+        let predicate = self.predicate.take().expect("Cannot poll twice");
+
+        if predicate(&guard) {
+            Ok(Async::Ready(Some(guard)))
+        } else {
+            guard.discard();
+            Ok(Async::Ready(None))
+        }
+    }
+}
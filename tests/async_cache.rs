@@ -0,0 +1,26 @@
+use futures::Future;
+use futures_tag_locks::{AsyncCache, RwLock, Tagged};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn computes_once_until_invalidated() {
+    let cache: RwLock<Option<Tagged<usize>>> = RwLock::new(None);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let compute = || {
+        let calls = calls.clone();
+        move || Ok::<_, ()>(Tagged::new(calls.fetch_add(1, Ordering::SeqCst)))
+    };
+
+    assert!(cache.get(&()).wait().unwrap().is_none());
+    assert_eq!(0, **cache.get_or_compute((), compute()).wait().unwrap());
+    assert_eq!(0, **cache.get_or_compute((), compute()).wait().unwrap());
+
+    cache.invalidate(&()).wait().unwrap();
+    assert!(cache.get(&()).wait().unwrap().is_none());
+    assert_eq!(1, **cache.get_or_compute((), compute()).wait().unwrap());
+
+    cache.insert((), Tagged::new(10)).wait().unwrap();
+    assert_eq!(Some(10), cache.get(&()).wait().unwrap().map(|v| **v));
+}
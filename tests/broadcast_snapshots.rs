@@ -0,0 +1,100 @@
+use futures::{Future, Stream};
+use futures_tag_locks::{RwLock, SetTag};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use version_tag::VersionTag;
+
+struct Counted {
+    clones: Arc<AtomicUsize>,
+    value: usize,
+}
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        self.clones.fetch_add(1, Ordering::SeqCst);
+
+        Self {
+            clones: self.clones.clone(),
+            value: self.value,
+        }
+    }
+}
+
+impl SetTag for Counted {
+    fn set_tag(&mut self, _: VersionTag) {}
+}
+
+#[test]
+fn receivers_share_one_snapshot_per_write() {
+    let clones = Arc::new(AtomicUsize::new(0));
+    let lock = RwLock::new(Counted {
+        clones: clones.clone(),
+        value: 0,
+    });
+
+    let receivers: Vec<_> = (0..3).map(|_| lock.broadcast_snapshots(4)).collect();
+
+    let tag = lock
+        .write()
+        .map(|mut w| {
+            w.value = 1;
+            w.new_tag()
+        })
+        .wait()
+        .unwrap();
+
+    assert_eq!(1, clones.load(Ordering::SeqCst));
+
+    let snapshots: Vec<_> = receivers
+        .into_iter()
+        .map(|rx| rx.into_future().wait().ok().unwrap().0.unwrap())
+        .collect();
+
+    for (t, snapshot) in &snapshots {
+        assert_eq!(tag, *t);
+        assert_eq!(1, snapshot.value);
+        assert!(Arc::ptr_eq(snapshot, &snapshots[0].1));
+    }
+}
+
+#[test]
+fn no_snapshot_once_the_receivers_are_dropped() {
+    let clones = Arc::new(AtomicUsize::new(0));
+    let lock = RwLock::new(Counted {
+        clones: clones.clone(),
+        value: 0,
+    });
+
+    drop(lock.broadcast_snapshots(4));
+
+    for _ in 0..2 {
+        lock.write().map(|mut w| w.value += 1).wait().unwrap();
+    }
+
+    assert_eq!(1, clones.load(Ordering::SeqCst));
+}
+
+#[test]
+fn a_full_receiver_keeps_the_latest_snapshots() {
+    let lock = RwLock::new_tagged(0);
+    let rx = lock.broadcast_snapshots(2);
+    let mut tags = Vec::new();
+
+    for v in 1..=3 {
+        tags.push(
+            lock.write()
+                .map(|mut w| {
+                    **w = v;
+                    w.new_tag()
+                })
+                .wait()
+                .unwrap(),
+        );
+    }
+
+    drop(lock);
+
+    let snapshots: Vec<_> = rx.map(|(tag, v)| (tag, **v)).collect().wait().unwrap();
+
+    assert_eq!(vec![(tags[1], 2), (tags[2], 3)], snapshots);
+}
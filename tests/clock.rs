@@ -0,0 +1,22 @@
+use futures_tag_locks::{Clock, SystemClock};
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn system_clock_runs_the_callbacks_in_order() {
+    let clock = SystemClock;
+    let (tx, rx) = mpsc::channel();
+    let now = clock.now();
+
+    for i in (0..3).rev() {
+        let tx = tx.clone();
+        let at = now + Duration::from_millis(10 * i);
+
+        clock.schedule(at, Box::new(move || tx.send(i).unwrap()));
+    }
+
+    let timeout = Duration::from_secs(5);
+    let order: Vec<_> = (0..3).map(|_| rx.recv_timeout(timeout).unwrap()).collect();
+
+    assert_eq!(vec![0, 1, 2], order);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "test-util")]
+
+use futures::{Async, Future, Stream};
+use futures_tag_locks::test_util::{Harness, MockClock};
+use futures_tag_locks::{RwLock, RwLockBuilder, TagObserver, Tagged};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use version_tag::VersionTag;
+
+const WINDOW: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<VersionTag>>);
+
+impl TagObserver for Recorder {
+    fn on_tag(&self, tag: VersionTag) {
+        self.0.lock().unwrap().push(tag);
+    }
+}
+
+fn coalescing(clock: &Arc<MockClock>) -> RwLock<Tagged<usize>> {
+    RwLockBuilder::new()
+        .clock(clock.clone())
+        .coalesce(WINDOW)
+        .build(Tagged::new(0))
+}
+
+fn write(lock: &RwLock<Tagged<usize>>, value: usize) -> VersionTag {
+    lock.write()
+        .map(|mut w| {
+            **w = value;
+            w.new_tag()
+        })
+        .wait()
+        .unwrap()
+}
+
+#[test]
+fn every_write_of_a_burst_has_its_own_tag() {
+    let clock = Arc::new(MockClock::new());
+    let lock = coalescing(&clock);
+
+    let first = write(&lock, 1);
+    let second = write(&lock, 2);
+
+    assert_ne!(first, second);
+    assert!(!lock.is_unchanged(first));
+    assert!(lock.is_unchanged(second));
+    assert_eq!(Some(second), lock.last_tag());
+}
+
+#[test]
+fn observers_are_notified_once_per_burst_with_the_latest_tag() {
+    let clock = Arc::new(MockClock::new());
+    let lock = coalescing(&clock);
+    let recorder = Arc::new(Recorder::default());
+
+    lock.observe(recorder.clone());
+
+    write(&lock, 1);
+    write(&lock, 2);
+    let last = write(&lock, 3);
+
+    assert!(recorder.0.lock().unwrap().is_empty());
+
+    clock.advance(WINDOW);
+    assert_eq!(vec![last], *recorder.0.lock().unwrap());
+
+    let next = write(&lock, 4);
+    clock.advance(WINDOW);
+    assert_eq!(vec![last, next], *recorder.0.lock().unwrap());
+}
+
+#[test]
+fn streams_are_woken_at_the_end_of_the_burst() {
+    let clock = Arc::new(MockClock::new());
+    let lock = coalescing(&clock);
+    let mut stream = Harness::new(lock.subscribe().into_future());
+
+    assert!(matches!(stream.poll(), Ok(Async::NotReady)));
+
+    write(&lock, 1);
+    let last = write(&lock, 2);
+
+    assert!(!stream.is_notified());
+
+    clock.advance(WINDOW);
+    assert!(stream.is_notified());
+
+    match stream.poll() {
+        Ok(Async::Ready((tag, _))) => assert_eq!(Some(last), tag),
+        _ => panic!("the stream should yield the latest tag"),
+    }
+}
+
+#[test]
+fn zero_window_notifies_on_each_write() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLockBuilder::new()
+        .clock(clock.clone())
+        .coalesce(Duration::ZERO)
+        .build(Tagged::new(0));
+    let recorder = Arc::new(Recorder::default());
+
+    lock.observe(recorder.clone());
+
+    let first = write(&lock, 1);
+    let second = write(&lock, 2);
+
+    assert_eq!(vec![first, second], *recorder.0.lock().unwrap());
+}
@@ -0,0 +1,60 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::executor::current_thread::block_on_all;
+
+#[test]
+fn commit_runs_finalizers_before_the_tag_is_visible() {
+    let lock = RwLock::new_tagged(1);
+    let seen = Arc::new(AtomicUsize::new(0));
+    let before = lock.last_tag();
+
+    {
+        let handle = lock.clone();
+        let seen = seen.clone();
+
+        lock.on_commit(move |v, _| {
+            assert_eq!(before, handle.last_tag());
+            seen.store(**v, Ordering::SeqCst);
+            Ok(())
+        });
+    }
+
+    let tag = block_on_all(lock.write().and_then(|mut w| {
+        **w = 2;
+        w.commit()
+    }))
+    .unwrap();
+
+    assert_eq!(2, seen.load(Ordering::SeqCst));
+    assert_eq!(Some(tag), lock.last_tag());
+}
+
+#[test]
+fn finalizer_can_register_another_finalizer() {
+    let lock = RwLock::new_tagged(1);
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    {
+        let handle = lock.clone();
+        let runs = runs.clone();
+
+        lock.on_commit(move |_, _| {
+            let runs = runs.clone();
+
+            runs.fetch_add(1, Ordering::SeqCst);
+            handle.on_commit(move |_, _| {
+                runs.fetch_add(10, Ordering::SeqCst);
+                Ok(())
+            });
+            Ok(())
+        });
+    }
+
+    block_on_all(lock.write().and_then(|w| w.commit())).unwrap();
+    assert_eq!(1, runs.load(Ordering::SeqCst));
+
+    block_on_all(lock.write().and_then(|w| w.commit())).unwrap();
+    assert_eq!(12, runs.load(Ordering::SeqCst));
+}
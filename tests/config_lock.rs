@@ -0,0 +1,28 @@
+use futures::{Future, Stream};
+use futures_tag_locks::ConfigLock;
+
+#[test]
+fn reloads_notify_the_changes() {
+    let config = ConfigLock::new("a".to_string());
+    let changed = config.changed();
+    let tag = config
+        .reload_from(Ok::<_, ()>("b".to_string()))
+        .wait()
+        .unwrap();
+
+    assert_eq!("b", config.current().wait().unwrap().as_str());
+    assert_eq!(tag, config.current().wait().unwrap().tag());
+
+    let (next, _) = changed.into_future().wait().ok().unwrap();
+    assert_eq!(Some(tag), next);
+}
+
+#[test]
+fn a_failed_reload_keeps_the_current_config() {
+    let config = ConfigLock::new(1);
+    let before = config.current().wait().unwrap().tag();
+
+    assert_eq!(Err("io"), config.reload_from(Err::<usize, _>("io")).wait());
+    assert_eq!(1, **config.current().wait().unwrap());
+    assert_eq!(before, config.current().wait().unwrap().tag());
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "test-util")]
+
+use futures::{Async, Future, Stream};
+use futures_tag_locks::test_util::{Harness, MockClock};
+use futures_tag_locks::{RwLock, Tagged};
+use std::sync::Arc;
+use std::time::Duration;
+use version_tag::VersionTag;
+
+const WINDOW: Duration = Duration::from_millis(100);
+
+fn write(lock: &RwLock<Tagged<usize>>, value: usize) -> VersionTag {
+    lock.write()
+        .map(|mut w| {
+            **w = value;
+            w.new_tag()
+        })
+        .wait()
+        .unwrap()
+}
+
+#[test]
+fn yields_the_latest_tag_once_per_window() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLock::with_clock(Tagged::new(0), clock.clone());
+    let mut changes = Harness::new(lock.subscribe().debounce(WINDOW).into_future());
+
+    let first = write(&lock, 1);
+
+    match changes.poll() {
+        Ok(Async::Ready((tag, stream))) => {
+            assert_eq!(Some(first), tag);
+            changes = Harness::new(stream.into_future());
+        }
+        _ => panic!("the first tag should be yielded right away"),
+    }
+
+    write(&lock, 2);
+    let last = write(&lock, 3);
+
+    assert!(matches!(changes.poll(), Ok(Async::NotReady)));
+
+    clock.advance(WINDOW / 2);
+    assert!(!changes.is_notified());
+
+    clock.advance(WINDOW / 2);
+    assert!(changes.is_notified());
+
+    match changes.poll() {
+        Ok(Async::Ready((tag, _))) => assert_eq!(Some(last), tag),
+        _ => panic!("the latest tag should be yielded at the end of the window"),
+    }
+}
+
+#[test]
+fn ends_when_the_lock_is_dropped() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLock::with_clock(Tagged::new(0), clock);
+    let changes = lock.subscribe().debounce(WINDOW);
+
+    drop(lock);
+    assert_eq!(Ok(vec![]), changes.collect().wait());
+}
@@ -0,0 +1,44 @@
+use futures::{Future, Stream};
+use futures_tag_locks::{DiagnosticEvent, RwLock};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[test]
+fn released_carries_the_committed_tag() {
+    let lock = RwLock::new_tagged(0);
+    let events = lock.diagnostics();
+    let tag = lock.write().wait().unwrap().finish();
+
+    drop(lock);
+
+    let events: Vec<_> = events.map(|d| d.event).collect().wait().unwrap();
+
+    assert_eq!(2, events.len());
+    assert_eq!(DiagnosticEvent::Acquired, events[0]);
+    assert!(matches!(events[1], DiagnosticEvent::Released { tag: Some(t), .. } if t == tag));
+}
+
+#[test]
+fn a_panicking_writer_does_not_commit() {
+    let lock = RwLock::new_tagged(0);
+    let events = lock.diagnostics();
+    let before = lock.read().wait().unwrap().tag();
+
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let mut w = lock.write().wait().unwrap();
+        **w = 1;
+        panic!("writer");
+    }));
+
+    assert!(res.is_err());
+    assert_eq!(before, lock.read().wait().unwrap().tag());
+    drop(lock);
+
+    let events: Vec<_> = events.map(|d| d.event).collect().wait().unwrap();
+
+    assert_eq!(3, events.len());
+    assert_eq!(DiagnosticEvent::Poisoned, events[1]);
+    assert!(matches!(
+        events[2],
+        DiagnosticEvent::Released { tag: None, .. }
+    ));
+}
@@ -0,0 +1,25 @@
+use futures_tag_locks::{etag, if_none_match, Tagged};
+
+#[test]
+fn etag_is_stable_within_the_process() {
+    let value = Tagged::new(10);
+    assert_eq!(etag(value.tag()), etag(value.tag()));
+}
+
+#[test]
+fn distinct_tags_have_distinct_etags() {
+    let first = Tagged::new(10);
+    let second = Tagged::new(10);
+
+    assert_ne!(etag(first.tag()), etag(second.tag()));
+    assert!(!if_none_match(&etag(first.tag()), second.tag()));
+}
+
+#[test]
+fn matches_weak_and_listed_etags() {
+    let value = Tagged::new(10);
+    let header = format!("\"other\", W/{}", etag(value.tag()));
+
+    assert!(if_none_match(&header, value.tag()));
+    assert!(if_none_match("*", value.tag()));
+}
@@ -0,0 +1,169 @@
+#![cfg(feature = "test-util")]
+
+use futures::future::poll_fn;
+use futures::{Async, Future};
+use futures_tag_locks::test_util::{Harness, MockClock};
+use futures_tag_locks::{Expiring, RwLock, RwLockBuilder, Tagged};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TTL: Duration = Duration::from_secs(60);
+
+#[test]
+fn expires_on_the_clock_of_the_lock() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLock::with_clock(None, clock.clone());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let read = || {
+        let calls = calls.clone();
+
+        lock.read_or_refresh(move || {
+            Ok::<_, ()>(Expiring::new(calls.fetch_add(1, Ordering::SeqCst), TTL))
+        })
+        .map(|v| **v)
+        .wait()
+        .unwrap()
+    };
+
+    assert_eq!(0, read());
+
+    clock.advance(TTL - Duration::from_millis(1));
+    assert_eq!(0, read());
+
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(1, read());
+}
+
+type Init = Box<dyn Future<Item = Expiring<usize>, Error = ()> + Send>;
+
+/// An initializer returning the number of calls so far, once `done` is set.
+fn slow_init(calls: &Arc<AtomicUsize>, done: &Arc<AtomicBool>) -> impl Fn() -> Init + Clone {
+    let calls = calls.clone();
+    let done = done.clone();
+
+    move || {
+        let value = calls.fetch_add(1, Ordering::SeqCst);
+        let done = done.clone();
+
+        Box::new(poll_fn(move || match done.swap(false, Ordering::SeqCst) {
+            true => Ok(Async::Ready(Expiring::new(value, TTL))),
+            false => Ok(Async::NotReady),
+        }))
+    }
+}
+
+/// Initializes the lock with an initializer taking 2s, then starts an early refresh
+/// 1s before the deadline.
+fn refreshing(
+    clock: &Arc<MockClock>,
+    calls: &Arc<AtomicUsize>,
+    done: &Arc<AtomicBool>,
+) -> (
+    RwLock<Option<Expiring<usize>>>,
+    Harness<impl Future<Item = usize, Error = ()>>,
+) {
+    let lock = RwLock::with_clock(None, clock.clone());
+    let mut first = Harness::new(lock.read_or_refresh_early(slow_init(calls, done)));
+
+    assert!(first.poll().unwrap().is_not_ready());
+    clock.advance(Duration::from_secs(2));
+    done.store(true, Ordering::SeqCst);
+    assert!(first.poll().unwrap().is_ready());
+    drop(first);
+
+    // -2s * ln(0.5) is about 1.4s, beyond the deadline.
+    clock.set_random(0.5);
+    clock.advance(TTL - Duration::from_secs(1));
+
+    let mut refresh = Harness::new(
+        lock.read_or_refresh_early(slow_init(calls, done))
+            .map(|v| **v),
+    );
+
+    assert!(refresh.poll().unwrap().is_not_ready());
+    assert_eq!(2, calls.load(Ordering::SeqCst));
+    (lock, refresh)
+}
+
+#[test]
+fn early_refresh_does_not_block_the_readers() {
+    let clock = Arc::new(MockClock::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let done = Arc::new(AtomicBool::new(false));
+    let (lock, mut refresh) = refreshing(&clock, &calls, &done);
+
+    let other = lock
+        .read_or_refresh_early(slow_init(&calls, &done))
+        .wait()
+        .unwrap();
+
+    assert_eq!(0, **other);
+    assert_eq!(2, calls.load(Ordering::SeqCst));
+    drop(other);
+
+    done.store(true, Ordering::SeqCst);
+    assert_eq!(Async::Ready(1), refresh.poll().unwrap());
+}
+
+#[test]
+fn early_refresh_loses_to_a_concurrent_write() {
+    let clock = Arc::new(MockClock::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let done = Arc::new(AtomicBool::new(false));
+    let (lock, mut refresh) = refreshing(&clock, &calls, &done);
+
+    lock.write()
+        .map(|mut w| *w = Some(Expiring::with_clock(10, TTL, &*clock)))
+        .wait()
+        .unwrap();
+
+    done.store(true, Ordering::SeqCst);
+    assert_eq!(Async::Ready(10), refresh.poll().unwrap());
+}
+
+#[test]
+fn no_early_refresh_by_default_on_the_mock_clock() {
+    let clock = Arc::new(MockClock::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let done = Arc::new(AtomicBool::new(true));
+    let lock = RwLock::with_clock(None, clock.clone());
+
+    lock.read_or_refresh_early(slow_init(&calls, &done))
+        .wait()
+        .unwrap();
+    clock.advance(TTL - Duration::from_nanos(1));
+    lock.read_or_refresh_early(slow_init(&calls, &done))
+        .wait()
+        .unwrap();
+
+    assert_eq!(1, calls.load(Ordering::SeqCst));
+}
+
+#[test]
+fn is_expired_reads_the_given_clock() {
+    let clock = MockClock::new();
+    let value = Expiring::with_clock(10, TTL, &clock);
+
+    assert!(!value.is_expired(&clock));
+
+    clock.advance(TTL);
+    assert!(value.is_expired(&clock));
+}
+
+#[test]
+fn history_is_timestamped_by_the_clock() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLockBuilder::new()
+        .clock(clock.clone())
+        .history(2)
+        .build(Tagged::new(0));
+
+    lock.write().map(|mut w| **w = 1).wait().unwrap();
+    clock.advance(TTL);
+    lock.write().map(|mut w| **w = 2).wait().unwrap();
+
+    let history = lock.history();
+    assert_eq!(TTL, history[1].at.duration_since(history[0].at).unwrap());
+}
@@ -0,0 +1,18 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn finish_commits_and_returns_the_tag() {
+    let lock = RwLock::new_tagged(0);
+    let mut guard = lock.write().wait().unwrap();
+
+    **guard = 1;
+    let expected = guard.new_tag();
+    let tag = guard.finish();
+
+    assert_eq!(expected, tag);
+    assert_eq!(Some(tag), lock.last_tag());
+
+    let value = lock.read().wait().unwrap();
+    assert_eq!((1, tag), (**value, value.tag()));
+}
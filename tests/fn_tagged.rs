@@ -0,0 +1,27 @@
+use futures::Future;
+use futures_tag_locks::{FnTagged, RwLock};
+use version_tag::VersionTag;
+
+type Tags = FnTagged<Vec<VersionTag>, fn(&mut Vec<VersionTag>, VersionTag)>;
+
+fn tagged() -> RwLock<Tags> {
+    RwLock::new(FnTagged::new(Vec::new(), |v, tag| v.push(tag)))
+}
+
+#[test]
+fn the_closure_receives_each_committed_tag() {
+    let lock = tagged();
+    let first = lock.write().wait().unwrap().finish();
+    let second = lock.write().wait().unwrap().finish();
+
+    assert_eq!(vec![first, second], **lock.read().wait().unwrap());
+}
+
+#[test]
+fn the_closure_is_not_called_without_a_commit() {
+    let lock = tagged();
+
+    assert!(lock.write_if(|_| false).wait().unwrap().is_none());
+    assert!(lock.read().wait().unwrap().is_empty());
+    assert!(FnTagged::new(vec![1], |_: &mut Vec<u8>, _| {}).into_inner() == [1]);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "test-util")]
+
+use futures::sync::mpsc::{self, Receiver};
+use futures::{Future, Stream};
+use futures_tag_locks::test_util::Harness;
+use futures_tag_locks::{FullPolicy, RwLock, Tagged};
+use version_tag::VersionTag;
+
+fn write(lock: &RwLock<Tagged<usize>>, value: usize) -> VersionTag {
+    lock.write()
+        .map(|mut w| {
+            **w = value;
+            w.new_tag()
+        })
+        .wait()
+        .unwrap()
+}
+
+/// Forwards and receives one tag at a time, until the queue of the lock is empty.
+fn forward(policy: FullPolicy, writes: usize) -> (Vec<VersionTag>, Vec<VersionTag>) {
+    let lock = RwLock::new_tagged(0);
+    let (tx, mut rx): (_, Receiver<_>) = mpsc::channel(0);
+    let mut forward = Harness::new(lock.forward_tags(tx, policy));
+    let written = (1..=writes).map(|v| write(&lock, v)).collect();
+    let mut received = Vec::new();
+
+    assert!(forward.poll().unwrap().is_not_ready());
+    drop(lock);
+
+    while forward.poll().unwrap().is_not_ready() || forward.is_notified() {
+        let (tag, rest) = rx.into_future().wait().ok().unwrap();
+
+        received.extend(tag);
+        rx = rest;
+    }
+
+    drop(forward);
+    received.extend(rx.collect().wait().unwrap());
+    (written, received)
+}
+
+#[test]
+fn keep_all_forwards_every_tag() {
+    let (written, received) = forward(FullPolicy::KeepAll, 4);
+    assert_eq!(written, received);
+}
+
+#[test]
+fn drop_oldest_forwards_the_newest_tag_when_full() {
+    let (written, received) = forward(FullPolicy::DropOldest, 4);
+    assert_eq!(vec![written[0], written[3]], received);
+}
+
+#[test]
+fn dropping_the_future_unobserves_the_lock() {
+    let lock = RwLock::new_tagged(0);
+    let (tx, _rx) = mpsc::channel(1);
+    let forward = lock.forward_tags(tx, FullPolicy::KeepAll);
+
+    let notified = lock.write_and_notify(|v| **v = 1).wait().unwrap();
+    assert_eq!(1, notified.observers);
+
+    drop(forward);
+
+    let notified = lock.write_and_notify(|v| **v = 2).wait().unwrap();
+    assert_eq!(0, notified.observers);
+}
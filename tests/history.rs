@@ -0,0 +1,68 @@
+use futures::Future;
+use futures_tag_locks::{RwLock, Tagged};
+use version_tag::VersionTag;
+
+fn write(lock: &RwLock<Tagged<usize>>, value: usize) -> VersionTag {
+    lock.write()
+        .map(|mut w| {
+            **w = value;
+            w.new_tag()
+        })
+        .wait()
+        .unwrap()
+}
+
+#[test]
+fn keeps_the_last_writes() {
+    let lock = RwLock::new_tagged(0);
+    lock.keep_history(2);
+
+    write(&lock, 1);
+    let second = write(&lock, 2);
+    let third = write(&lock, 3);
+
+    let tags: Vec<_> = lock.history().iter().map(|e| e.tag).collect();
+
+    assert_eq!(vec![second, third], tags);
+    assert_eq!(Some(third), lock.last_writer().map(|e| e.tag));
+}
+
+#[test]
+fn is_empty_unless_enabled() {
+    let lock = RwLock::new_tagged(0);
+
+    write(&lock, 1);
+
+    assert!(lock.history().is_empty());
+    assert!(lock.last_writer().is_none());
+}
+
+#[test]
+fn records_the_reason() {
+    let lock = RwLock::new_tagged(0);
+    lock.keep_history(1);
+
+    lock.write()
+        .map(|mut w| w.set_reason("import"))
+        .wait()
+        .unwrap();
+    assert_eq!(Some("import"), lock.last_writer().unwrap().reason);
+
+    write(&lock, 1);
+    assert_eq!(None, lock.last_writer().unwrap().reason);
+}
+
+#[test]
+fn records_the_actor() {
+    let lock = RwLock::new_tagged(0);
+    lock.keep_history(1);
+
+    lock.write()
+        .map(|mut w| w.set_actor("alice"))
+        .wait()
+        .unwrap();
+    assert_eq!(Some("alice"), lock.last_writer().unwrap().actor.as_deref());
+
+    write(&lock, 1);
+    assert_eq!(None, lock.last_writer().unwrap().actor);
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "debug-tracking")]
+
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn records_where_the_write_lock_was_acquired() {
+    let lock = RwLock::new_tagged(0);
+    assert!(lock.holder().is_none());
+
+    let line = line!() + 1;
+    let guard = lock.write().wait().unwrap();
+    let holder = lock.holder().unwrap();
+
+    assert_eq!(file!(), holder.location.file());
+    assert_eq!(line, holder.location.line());
+
+    drop(guard);
+    assert!(lock.holder().is_none());
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "test-util")]
+
+use futures::future::poll_fn;
+use futures::{Async, Future, Stream};
+use futures_tag_locks::test_util::{Harness, MockClock};
+use futures_tag_locks::{Clock, Expiring, RwLock, RwLockBuilder, TagObserver, Tagged};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use version_tag::VersionTag;
+
+const TTL: Duration = Duration::from_secs(10);
+
+fn expiring(clock: &Arc<MockClock>, value: usize) -> impl Fn() -> Result<Expiring<usize>, ()> {
+    let clock = clock.clone();
+    move || Ok(Expiring::with_deadline(value, clock.now() + TTL))
+}
+
+#[test]
+fn read_or_init_commits_a_tag() {
+    let lock = RwLock::new(None);
+    assert_eq!(None, lock.last_tag());
+
+    let value = lock
+        .read_or_init(|| Ok::<_, ()>(String::from("loaded")))
+        .map(|v| v.clone())
+        .wait()
+        .unwrap();
+
+    assert_eq!("loaded", value);
+    assert!(lock.last_tag().is_some());
+}
+
+#[test]
+fn refresh_changes_the_tag() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLock::with_clock(None, clock.clone());
+
+    lock.read_or_refresh(expiring(&clock, 1)).wait().unwrap();
+    let seen = lock.last_tag().unwrap();
+
+    lock.read_or_refresh(expiring(&clock, 2)).wait().unwrap();
+    assert!(lock.is_unchanged(seen));
+
+    clock.advance(TTL);
+
+    let value = lock
+        .read_or_refresh(expiring(&clock, 3))
+        .map(|v| **v)
+        .wait()
+        .unwrap();
+    assert_eq!(3, value);
+    assert!(!lock.is_unchanged(seen));
+}
+
+#[test]
+fn failed_init_does_not_change_the_tag() {
+    let lock = RwLock::<Option<Tagged<usize>>>::new(None);
+
+    assert!(lock
+        .read_or_init(|| Err::<Tagged<usize>, _>(()))
+        .wait()
+        .is_err());
+    assert_eq!(None, lock.last_tag());
+}
+
+#[test]
+fn readers_can_check_for_a_running_initializer() {
+    let lock = RwLock::new(None);
+    let done = Arc::new(AtomicBool::new(false));
+
+    let init = {
+        let done = done.clone();
+
+        move || {
+            let done = done.clone();
+
+            poll_fn(move || match done.load(Ordering::SeqCst) {
+                true => Ok::<_, ()>(Async::Ready(Tagged::new(1))),
+                false => Ok(Async::NotReady),
+            })
+        }
+    };
+
+    let mut read = Harness::new(lock.read_or_init(init));
+    let mut initialized = Harness::new(lock.initialized());
+
+    assert!(!lock.is_initializing());
+    assert!(read.poll().unwrap().is_not_ready());
+    assert!(lock.is_initializing());
+    assert!(initialized.poll().unwrap().is_not_ready());
+
+    done.store(true, Ordering::SeqCst);
+    assert!(read.poll().unwrap().is_ready());
+
+    assert!(!lock.is_initializing());
+    assert!(initialized.is_notified());
+    assert!(initialized.poll().unwrap().is_ready());
+}
+
+#[derive(Default)]
+struct Counter(AtomicUsize);
+
+impl TagObserver for Counter {
+    fn on_tag(&self, _: VersionTag) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn refresh_is_seen_by_the_subscribers() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLockBuilder::new()
+        .clock(clock.clone())
+        .history(4)
+        .build(None);
+    let counter = Arc::new(Counter::default());
+    let values = lock.value_stream();
+
+    lock.observe(counter.clone());
+
+    lock.read_or_refresh(expiring(&clock, 1)).wait().unwrap();
+    clock.advance(TTL);
+    lock.read_or_refresh(expiring(&clock, 2)).wait().unwrap();
+
+    assert_eq!(2, counter.0.load(Ordering::SeqCst));
+    assert_eq!(2, lock.history().len());
+    assert_eq!(
+        Some(lock.last_tag().unwrap()),
+        lock.history().last().map(|e| e.tag)
+    );
+    assert!(lock.holder().is_none());
+
+    drop(lock);
+
+    let values: Vec<_> = values.map(|(_, v)| *v.unwrap()).collect().wait().unwrap();
+    assert_eq!(vec![1, 2], values);
+}
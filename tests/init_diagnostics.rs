@@ -0,0 +1,89 @@
+#![cfg(feature = "test-util")]
+
+use futures::future::poll_fn;
+use futures::{Async, Future, Stream};
+use futures_tag_locks::test_util::{Harness, MockClock};
+use futures_tag_locks::{DiagnosticEvent, RwLock, Tagged};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn timer_starts_on_first_poll() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLock::with_clock(None, clock.clone());
+    let events = lock.diagnostics();
+    let read = lock.read_or_init(|| Ok::<_, ()>(Tagged::new(1)));
+
+    clock.advance(Duration::from_secs(5));
+    read.wait().unwrap();
+    drop(lock);
+
+    let events: Vec<_> = events.map(|d| d.event).collect().wait().unwrap();
+
+    assert!(events.contains(&DiagnosticEvent::Initialized {
+        init: Duration::default(),
+        waited: Duration::default(),
+    }));
+}
+
+#[test]
+fn reports_waiters_served_by_another_initializer() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLock::with_clock(None, clock.clone());
+    let events = lock.diagnostics();
+    let done = Arc::new(AtomicBool::new(false));
+
+    let init = {
+        let done = done.clone();
+
+        move || {
+            let done = done.clone();
+
+            poll_fn(move || match done.load(Ordering::SeqCst) {
+                true => Ok::<_, ()>(Async::Ready(Tagged::new(1))),
+                false => Ok(Async::NotReady),
+            })
+        }
+    };
+
+    let mut first = Harness::new(lock.read_or_init(init.clone()));
+    let mut second = Harness::new(lock.read_or_init(init));
+
+    assert!(first.poll().unwrap().is_not_ready());
+    assert!(second.poll().unwrap().is_not_ready());
+
+    clock.advance(Duration::from_secs(2));
+    done.store(true, Ordering::SeqCst);
+
+    assert!(first.poll().unwrap().is_ready());
+    assert!(second.poll().unwrap().is_ready());
+
+    drop((first, second));
+    drop(lock);
+
+    let events: Vec<_> = events
+        .map(|d| d.event)
+        .filter(|e| {
+            matches!(
+                e,
+                DiagnosticEvent::Initialized { .. } | DiagnosticEvent::InitializedByOther { .. }
+            )
+        })
+        .collect()
+        .wait()
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            DiagnosticEvent::Initialized {
+                init: Duration::from_secs(2),
+                waited: Duration::default(),
+            },
+            DiagnosticEvent::InitializedByOther {
+                waited: Duration::from_secs(2),
+            },
+        ],
+        events
+    );
+}
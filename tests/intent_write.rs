@@ -0,0 +1,42 @@
+#![cfg(feature = "test-util")]
+
+use futures::{Async, Future};
+use futures_tag_locks::test_util::Harness;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn one_intent_at_a_time_without_blocking_the_readers() {
+    let lock = RwLock::new_tagged(1);
+    let intent = lock.intent_write().wait().unwrap();
+    let mut second = Harness::new(lock.intent_write());
+
+    assert!(second.poll().unwrap().is_not_ready());
+    assert_eq!(1, **lock.read().wait().unwrap());
+    assert_eq!(1, **intent.read().wait().unwrap());
+
+    drop(intent);
+
+    assert!(second.is_notified());
+    assert!(second.poll().unwrap().is_ready());
+}
+
+#[test]
+fn the_upgrade_waits_for_the_readers_and_keeps_the_intent() {
+    let lock = RwLock::new_tagged(1);
+    let read = lock.read().wait().unwrap();
+    let mut upgrade = Harness::new(lock.intent_write().wait().unwrap().upgrade());
+    let mut other = Harness::new(lock.intent_write());
+
+    assert!(upgrade.poll().unwrap().is_not_ready());
+    assert!(other.poll().unwrap().is_not_ready());
+
+    drop(read);
+
+    let guard = match upgrade.poll() {
+        Ok(Async::Ready(guard)) => guard,
+        _ => panic!("the upgrade should get the write lock"),
+    };
+
+    assert!(other.poll().unwrap().is_ready());
+    drop(guard);
+}
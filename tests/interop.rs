@@ -0,0 +1,27 @@
+use futures::Future;
+use futures_tag_locks::{RwLock, Tagged};
+
+#[test]
+fn adopts_a_futures_locks_rwlock() {
+    let raw = futures_locks::RwLock::new(Tagged::new(1));
+    let lock = RwLock::from(raw.clone());
+
+    lock.write().map(|mut w| **w = 2).wait().unwrap();
+    assert_eq!(2, **raw.read().wait().unwrap());
+
+    // the writes through the raw lock bypass the tagging.
+    let seen = lock.last_tag().unwrap();
+    raw.write().map(|mut w| **w = 3).wait().unwrap();
+
+    assert!(lock.is_unchanged(seen));
+    assert_eq!(3, lock.read().map(|v| **v).wait().unwrap());
+}
+
+#[test]
+fn into_inner_lock_shares_the_value() {
+    let lock = RwLock::new_tagged(1);
+    let raw = lock.clone().into_inner_lock();
+
+    lock.write().map(|mut w| **w = 2).wait().unwrap();
+    assert_eq!(2, **raw.read().wait().unwrap());
+}
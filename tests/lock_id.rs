@@ -0,0 +1,19 @@
+use futures_tag_locks::{RwLock, RwLockBuilder};
+
+#[test]
+fn is_shared_by_the_clones_and_ordered_by_creation() {
+    let first = RwLock::new_tagged(0);
+    let second = RwLock::new_tagged(0);
+
+    assert_eq!(first.id(), first.clone().id());
+    assert_eq!(first.id(), first.downgrade().upgrade().unwrap().id());
+    assert!(first.id() < second.id());
+}
+
+#[test]
+fn name_is_set_by_the_builder() {
+    let lock = RwLockBuilder::new().name("users").build(0);
+
+    assert_eq!(Some("users"), lock.name());
+    assert_eq!(None, RwLock::new(0).name());
+}
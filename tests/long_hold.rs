@@ -0,0 +1,68 @@
+#![cfg(feature = "test-util")]
+
+use futures::Future;
+use futures_tag_locks::test_util::MockClock;
+use futures_tag_locks::{LongHold, RwLock, RwLockBuilder, Tagged};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const THRESHOLD: Duration = Duration::from_millis(100);
+
+#[test]
+fn reports_the_guards_held_too_long() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLockBuilder::new()
+        .clock(clock.clone())
+        .name("config")
+        .build(Tagged::new(0));
+    let reports: Arc<Mutex<Vec<LongHold>>> = Default::default();
+
+    {
+        let reports = reports.clone();
+        lock.on_long_hold(THRESHOLD, move |r| reports.lock().unwrap().push(r.clone()));
+    }
+
+    let guard = lock.write().wait().unwrap();
+    clock.advance(THRESHOLD);
+    drop(guard);
+
+    assert!(reports.lock().unwrap().is_empty());
+
+    let guard = lock.write().wait().unwrap();
+    let tag = guard.new_tag();
+    clock.advance(THRESHOLD * 2);
+    drop(guard);
+
+    let reports = reports.lock().unwrap();
+
+    assert_eq!(1, reports.len());
+    assert_eq!(THRESHOLD * 2, reports[0].held);
+    assert_eq!(lock.id(), reports[0].id);
+    assert_eq!(Some("config"), reports[0].name.as_deref());
+    assert_eq!(tag, reports[0].tag);
+}
+
+#[test]
+fn the_report_can_replace_itself() {
+    let clock = Arc::new(MockClock::new());
+    let lock = RwLock::with_clock(Tagged::new(0), clock.clone());
+    let reports = Arc::new(Mutex::new(0));
+
+    {
+        let handle = lock.clone();
+        let reports = reports.clone();
+
+        lock.on_long_hold(THRESHOLD, move |_| {
+            *reports.lock().unwrap() += 1;
+            handle.on_long_hold(THRESHOLD, |_| {});
+        });
+    }
+
+    for _ in 0..2 {
+        let guard = lock.write().wait().unwrap();
+        clock.advance(THRESHOLD * 2);
+        drop(guard);
+    }
+
+    assert_eq!(1, *reports.lock().unwrap());
+}
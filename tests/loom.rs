@@ -0,0 +1,49 @@
+//! Model checks of the internal synchronization, run with:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//! ```
+#![cfg(loom)]
+
+use futures_tag_locks::TaggedAtomic;
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn load_never_sees_a_torn_store() {
+    loom::model(|| {
+        let atomic = Arc::new(TaggedAtomic::new(0usize));
+        let initial = atomic.tag();
+
+        let writer = {
+            let atomic = atomic.clone();
+            thread::spawn(move || atomic.store(1))
+        };
+
+        let (value, tag) = atomic.load();
+        let stored = writer.join().unwrap();
+
+        assert!((value, tag) == (0, initial) || (value, tag) == (1, stored));
+        assert_eq!((1, stored), atomic.load());
+    });
+}
+
+#[test]
+fn concurrent_updates_are_serialized() {
+    loom::model(|| {
+        let atomic = Arc::new(TaggedAtomic::new(0usize));
+
+        let writers: Vec<_> = (0..2)
+            .map(|_| {
+                let atomic = atomic.clone();
+                thread::spawn(move || atomic.update(|v| *v += 1))
+            })
+            .collect();
+
+        let tags: Vec<_> = writers.into_iter().map(|w| w.join().unwrap()).collect();
+        let (value, tag) = atomic.load();
+
+        assert_eq!(2, value);
+        assert!(tags.contains(&tag));
+    });
+}
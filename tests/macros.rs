@@ -0,0 +1,26 @@
+use futures::Future;
+use futures_tag_locks::tagged_rwlock;
+
+tagged_rwlock! {
+    static COUNTER: usize = 1;
+    pub(crate) static NAMES: Vec<&'static str> = vec!["a"];
+}
+
+#[test]
+fn static_locks_are_shared() {
+    **COUNTER.write().wait().unwrap() += 1;
+    NAMES.write().wait().unwrap().push("b");
+
+    assert_eq!(2, **COUNTER.read().wait().unwrap());
+    assert_eq!(vec!["a", "b"], **NAMES.read().wait().unwrap());
+}
+
+#[test]
+fn expression_locks_are_tagged() {
+    let lock = tagged_rwlock!(vec![1, 2, 3]);
+    let tag = lock.read().wait().unwrap().tag();
+
+    lock.write().wait().unwrap().push(4);
+
+    assert_ne!(tag, lock.read().wait().unwrap().tag());
+}
@@ -0,0 +1,25 @@
+use futures::Future;
+use futures_tag_locks::{Merge, RwLock};
+use std::collections::BTreeSet;
+
+struct GrowSet(BTreeSet<u32>);
+
+impl Merge<Vec<u32>> for GrowSet {
+    fn merge(&mut self, other: Vec<u32>) -> bool {
+        let len = self.0.len();
+        self.0.extend(other);
+        self.0.len() != len
+    }
+}
+
+#[test]
+fn bumps_the_tag_only_when_the_merge_changed_the_state() {
+    let lock = RwLock::new_tagged(GrowSet(BTreeSet::new()));
+    let tag = lock.merge(vec![1, 2]).wait().unwrap();
+
+    assert_eq!(Some(lock.read().wait().unwrap().tag()), tag);
+    assert_eq!(None, lock.merge(vec![2, 1]).wait().unwrap());
+    assert_eq!(tag, Some(lock.read().wait().unwrap().tag()));
+    assert!(lock.merge(vec![3]).wait().unwrap().is_some());
+    assert_eq!(3, lock.read().wait().unwrap().0.len());
+}
@@ -0,0 +1,15 @@
+#![cfg(feature = "watch")]
+
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn mirrors_every_write() {
+    let lock = RwLock::new_tagged(1);
+    let rx = lock.mirror_to_watch().wait().unwrap();
+
+    assert_eq!(1, **rx.get_ref());
+
+    lock.write().map(|mut w| **w = 2).wait().unwrap();
+    assert_eq!(2, **rx.get_ref());
+}
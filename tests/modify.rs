@@ -0,0 +1,28 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn a_no_op_does_not_bump_the_tag() {
+    let lock = RwLock::new_tagged(vec![1]);
+    let before = lock.read().wait().unwrap().tag();
+
+    assert_eq!(None, lock.modify(|v| v.contains(&2)).wait().unwrap());
+    assert_eq!(before, lock.read().wait().unwrap().tag());
+}
+
+#[test]
+fn a_change_bumps_the_tag() {
+    let lock = RwLock::new_tagged(vec![1]);
+    let tag = lock
+        .modify(|v| {
+            v.push(2);
+            true
+        })
+        .wait()
+        .unwrap();
+
+    let value = lock.read().wait().unwrap();
+
+    assert_eq!(Some(value.tag()), tag);
+    assert_eq!(vec![1, 2], **value);
+}
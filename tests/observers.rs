@@ -0,0 +1,68 @@
+use futures::Future;
+use futures_tag_locks::{ChangeEvent, RwLock, TagObserver};
+use std::sync::{Arc, Mutex};
+use version_tag::VersionTag;
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<VersionTag>>);
+
+impl TagObserver for Recorder {
+    fn on_tag(&self, tag: VersionTag) {
+        self.0.lock().unwrap().push(tag);
+    }
+}
+
+#[test]
+fn observers_can_be_added_and_removed() {
+    let lock = RwLock::new_tagged(0);
+    let recorder = Arc::new(Recorder::default());
+    let observer: Arc<dyn TagObserver> = recorder.clone();
+
+    lock.observe(observer.clone());
+
+    let notified = lock.write_and_notify(|v| **v = 1).wait().unwrap();
+    assert_eq!(vec![notified.tag], *recorder.0.lock().unwrap());
+
+    assert!(lock.unobserve(&observer));
+    assert!(!lock.unobserve(&observer));
+
+    lock.write_and_notify(|v| **v = 2).wait().unwrap();
+    assert_eq!(1, recorder.0.lock().unwrap().len());
+}
+
+#[derive(Default)]
+struct Changes(Mutex<Vec<ChangeEvent>>);
+
+impl TagObserver for Changes {
+    fn on_tag(&self, _: VersionTag) {}
+
+    fn on_change(&self, event: &ChangeEvent) {
+        self.0.lock().unwrap().push(event.clone());
+    }
+}
+
+#[test]
+fn observers_receive_the_metadata_of_the_write() {
+    let lock = RwLock::new_tagged(0);
+    let changes = Arc::new(Changes::default());
+
+    lock.observe(changes.clone());
+
+    let tag = lock
+        .write()
+        .map(|mut w| {
+            w.set_actor("alice");
+            w.set_label("import");
+            w.set_reason("nightly");
+            w.new_tag()
+        })
+        .wait()
+        .unwrap();
+
+    let events = changes.0.lock().unwrap();
+
+    assert_eq!(tag, events[0].tag);
+    assert_eq!(Some("alice"), events[0].actor.as_deref());
+    assert_eq!(Some("import"), events[0].label.as_deref());
+    assert_eq!(Some("nightly"), events[0].reason);
+}
@@ -0,0 +1,25 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn receives_the_old_and_new_values() {
+    let lock = RwLock::new_tagged(1);
+    let diffs = Arc::new(Mutex::new(Vec::new()));
+
+    {
+        let diffs = diffs.clone();
+        lock.on_diff(move |old, new, tag| diffs.lock().unwrap().push((**old, **new, tag)));
+    }
+
+    let tag = lock
+        .write()
+        .map(|mut w| {
+            **w = 2;
+            w.new_tag()
+        })
+        .wait()
+        .unwrap();
+
+    assert_eq!(vec![(1, 2, tag)], *diffs.lock().unwrap());
+}
@@ -0,0 +1,63 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct NotClone(usize);
+
+#[test]
+fn locked_hooks_do_not_need_clone() {
+    let lock = RwLock::new_tagged(NotClone(0));
+    let seen = Arc::new(AtomicUsize::new(0));
+
+    {
+        let seen = seen.clone();
+        lock.on_write(move |v, _| seen.store(v.0, Ordering::SeqCst));
+    }
+
+    lock.write().wait().unwrap().0 = 3;
+    assert_eq!(3, seen.load(Ordering::SeqCst));
+}
+
+#[test]
+fn a_panicking_hook_does_not_break_later_writes() {
+    let lock = RwLock::new_tagged(0);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    {
+        let calls = calls.clone();
+        lock.on_write(move |v, _| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            assert!(**v != 1, "hook");
+        });
+    }
+
+    let res = catch_unwind(AssertUnwindSafe(|| **lock.write().wait().unwrap() = 1));
+    assert!(res.is_err());
+
+    **lock.write().wait().unwrap() = 2;
+    assert_eq!(2, calls.load(Ordering::SeqCst));
+}
+
+#[test]
+fn hooks_are_skipped_when_the_writer_panics() {
+    let lock = RwLock::new_tagged(0);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    {
+        let calls = calls.clone();
+        lock.on_write(move |_, _| {
+            calls.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let mut w = lock.write().wait().unwrap();
+        **w = 1;
+        panic!("writer");
+    }));
+
+    assert!(res.is_err());
+    assert_eq!(0, calls.load(Ordering::SeqCst));
+}
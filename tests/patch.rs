@@ -0,0 +1,27 @@
+use futures::Future;
+use futures_tag_locks::{Patch, RwLock, Tagged};
+
+struct Set(usize, u32);
+
+impl Patch<Tagged<Vec<u32>>> for Set {
+    fn apply(self, value: &mut Tagged<Vec<u32>>) -> bool {
+        let changed = value[self.0] != self.1;
+        value[self.0] = self.1;
+        changed
+    }
+}
+
+#[test]
+fn bumps_the_tag_only_when_the_patch_changed_the_value() {
+    let lock = RwLock::new_tagged(vec![0, 0]);
+    let before = lock.read().wait().unwrap().tag();
+
+    assert_eq!(None, lock.apply_patch(Set(0, 0)).wait().unwrap());
+    assert_eq!(before, lock.read().wait().unwrap().tag());
+
+    let tag = lock.apply_patch(Set(1, 5)).wait().unwrap();
+    let value = lock.read().wait().unwrap();
+
+    assert_eq!(Some(value.tag()), tag);
+    assert_eq!(vec![0, 5], **value);
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "test-util")]
+
+use futures::{Future, Stream};
+use futures_tag_locks::test_util::Harness;
+use futures_tag_locks::{DiagnosticEvent, RwLock, RwLockBuilder, Tagged};
+
+fn bounded(max: usize) -> RwLock<Tagged<usize>> {
+    RwLockBuilder::new()
+        .max_write_waiters(max)
+        .build(Tagged::new(0))
+}
+
+#[test]
+fn fails_once_the_queue_is_full() {
+    let lock = bounded(1);
+    let events = lock.diagnostics();
+    let guard = lock.write().wait().unwrap();
+
+    let mut waiting = Harness::new(lock.queue_write().unwrap());
+    assert!(waiting.poll().unwrap().is_not_ready());
+
+    assert_eq!(1, lock.queue_write().err().unwrap().max);
+
+    drop(guard);
+    assert!(waiting.poll().unwrap().is_ready());
+    drop(waiting);
+
+    assert!(lock.queue_write().is_ok());
+    drop(lock);
+
+    let events: Vec<_> = events.map(|d| d.event).collect().wait().unwrap();
+    assert!(events.contains(&DiagnosticEvent::QueueFull { max: 1 }));
+}
+
+#[test]
+fn plain_writes_count_toward_the_limit() {
+    let lock = bounded(1);
+    let guard = lock.write().wait().unwrap();
+    let mut waiting = Harness::new(lock.write());
+
+    assert!(waiting.poll().unwrap().is_not_ready());
+    assert!(lock.queue_write().is_err());
+
+    drop(guard);
+    assert!(waiting.poll().unwrap().is_ready());
+}
+
+#[test]
+fn is_unbounded_by_default() {
+    let lock = RwLock::new_tagged(0);
+    let _guard = lock.write().wait().unwrap();
+    let waiting: Vec<_> = (0..10).map(|_| lock.queue_write()).collect();
+
+    assert!(waiting.iter().all(Result::is_ok));
+}
+
+#[test]
+fn queue_full_names_the_lock() {
+    let lock = RwLockBuilder::new()
+        .max_write_waiters(0)
+        .name("orders")
+        .build(Tagged::new(0));
+
+    let full = lock.queue_write().err().unwrap();
+
+    assert_eq!(lock.id(), full.id);
+    assert_eq!(Some("orders"), full.name.as_deref());
+    assert_eq!(
+        format!("orders ({}): write queue full (0 waiters)", lock.id()),
+        full.to_string()
+    );
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "test-util")]
+
+use futures::future::{self, poll_fn};
+use futures::{Async, Future};
+use futures_tag_locks::test_util::Harness;
+use futures_tag_locks::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+type Step = Box<dyn Future<Item = usize, Error = ()>>;
+
+#[test]
+fn starts_over_when_a_write_lands_during_the_step() {
+    let lock = RwLock::new_tagged(1);
+    let done = Arc::new(AtomicBool::new(false));
+    let mut calls = 0;
+
+    let fut = {
+        let done = done.clone();
+
+        lock.read_latest(move |v| -> Step {
+            let value = **v;
+            calls += 1;
+
+            match calls {
+                1 => {
+                    let done = done.clone();
+
+                    Box::new(poll_fn(move || match done.load(Ordering::SeqCst) {
+                        true => Ok(Async::Ready(value)),
+                        false => Ok(Async::NotReady),
+                    }))
+                }
+                _ => Box::new(future::ok(value)),
+            }
+        })
+    };
+
+    let mut fut = Harness::new(fut);
+    assert!(fut.poll().unwrap().is_not_ready());
+
+    let tag = lock.write_and_notify(|v| **v = 2).wait().unwrap().tag;
+    done.store(true, Ordering::SeqCst);
+
+    match fut.poll() {
+        Ok(Async::Ready(result)) => assert_eq!((2, Some(tag)), result),
+        _ => panic!("the step should have run again on the new value"),
+    }
+}
+
+#[test]
+fn resolves_when_no_write_lands() {
+    let lock = RwLock::new_tagged(1);
+    let (value, tag) = lock
+        .read_latest(|v| future::ok::<_, ()>(**v))
+        .wait()
+        .unwrap();
+
+    assert_eq!(1, value);
+    assert_eq!(lock.last_tag(), tag);
+}
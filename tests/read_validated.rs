@@ -0,0 +1,52 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+use std::cell::Cell;
+
+#[test]
+fn retries_after_a_concurrent_write() {
+    let lock = RwLock::new_tagged(0);
+    let calls = Cell::new(0);
+    let fut = lock.read_validated(|_| {
+        calls.set(calls.get() + 1);
+
+        match calls.get() {
+            1 => Err("partial"),
+            _ => Ok(()),
+        }
+    });
+
+    let tag = lock.write().wait().unwrap().finish();
+    let (guard, validated) = fut.wait().unwrap();
+
+    assert_eq!(2, calls.get());
+    assert_eq!(Some(tag), validated);
+    assert_eq!(tag, guard.tag());
+}
+
+#[test]
+fn fails_without_a_concurrent_write() {
+    let lock = RwLock::new_tagged(0);
+    let calls = Cell::new(0);
+    let fut = lock.read_validated(|_| {
+        calls.set(calls.get() + 1);
+        Err::<(), _>("invalid")
+    });
+
+    assert_eq!(Some("invalid"), fut.wait().err());
+    assert_eq!(1, calls.get());
+}
+
+#[test]
+fn fails_once_the_tag_is_stable() {
+    let lock = RwLock::new_tagged(0);
+    let calls = Cell::new(0);
+    let fut = lock.read_validated(|_| {
+        calls.set(calls.get() + 1);
+        Err::<(), _>("invalid")
+    });
+
+    lock.write().wait().unwrap();
+
+    assert!(fut.wait().is_err());
+    assert_eq!(2, calls.get());
+}
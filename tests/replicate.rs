@@ -0,0 +1,44 @@
+use futures::sync::mpsc;
+use futures::{Future, Stream};
+use futures_tag_locks::RwLock;
+
+#[test]
+fn a_follower_mirrors_the_commits_in_order() {
+    let lock = RwLock::new_tagged(0);
+    let (tx, rx) = mpsc::unbounded();
+
+    lock.replicate(tx, |v| **v * 10);
+
+    let tags: Vec<_> = (1..=3)
+        .map(|v| {
+            lock.write()
+                .map(move |mut w| {
+                    **w = v;
+                    w.finish()
+                })
+                .wait()
+                .unwrap()
+        })
+        .collect();
+
+    drop(lock);
+
+    let events: Vec<_> = rx.collect().wait().unwrap();
+    let expected: Vec<_> = tags.into_iter().zip(vec![10, 20, 30]).collect();
+
+    assert_eq!(expected, events);
+}
+
+#[test]
+fn stops_when_the_receiver_is_dropped() {
+    let lock = RwLock::new_tagged(0);
+    let (tx, rx) = mpsc::unbounded();
+
+    lock.replicate(tx, |v| **v);
+    drop(rx);
+
+    lock.write().wait().unwrap();
+    let notified = lock.write_and_notify(|v| **v = 1).wait().unwrap();
+
+    assert_eq!(0, notified.hooks);
+}
@@ -0,0 +1,14 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn the_lock_is_not_taken_before_the_first_poll() {
+    let lock = RwLock::new_tagged(1);
+    let request = lock.request_write();
+
+    **lock.write().wait().unwrap() = 2;
+    assert_eq!(2, **lock.read().wait().unwrap());
+
+    request.map(|mut w| **w = 3).wait().unwrap();
+    assert_eq!(3, **lock.read().wait().unwrap());
+}
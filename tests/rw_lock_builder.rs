@@ -0,0 +1,34 @@
+use futures::Future;
+use futures_tag_locks::{RwLockBuilder, Tagged};
+
+#[test]
+fn default_options_match_a_new_lock() {
+    let lock = RwLockBuilder::new().build(Tagged::new(0));
+
+    lock.write().wait().unwrap();
+
+    assert_eq!(None, lock.name());
+    assert!(lock.history().is_empty());
+    assert!(lock.queue_write().is_ok());
+}
+
+#[test]
+fn applies_every_option() {
+    let lock = RwLockBuilder::new()
+        .name("cfg")
+        .history(2)
+        .max_write_waiters(1)
+        .build(Tagged::new(0));
+
+    for v in 1..=3 {
+        **lock.write().wait().unwrap() = v;
+    }
+
+    assert_eq!(Some("cfg"), lock.name());
+    assert_eq!(2, lock.history().len());
+
+    let _guard = lock.write().wait().unwrap();
+    let _queued = lock.queue_write().unwrap();
+
+    assert!(lock.queue_write().is_err());
+}
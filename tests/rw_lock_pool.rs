@@ -0,0 +1,71 @@
+use futures::{Future, Stream};
+use futures_tag_locks::{RwLockPool, TagObserver, Tagged, VersionTag};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::executor::current_thread::block_on_all;
+
+#[derive(Default)]
+struct Counter(AtomicUsize);
+
+impl TagObserver for Counter {
+    fn on_tag(&self, _: VersionTag) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn recycled_lock_starts_over() {
+    let pool = RwLockPool::new(1);
+    let old = pool.get(Tagged::new(1));
+    let counter = Arc::new(Counter::default());
+
+    old.observe(counter.clone());
+    block_on_all(old.write().map(|mut w| **w = 2)).unwrap();
+
+    let old_id = old.id();
+    pool.recycle(old);
+
+    let new = pool.get(Tagged::new(3));
+    block_on_all(new.write().map(|mut w| **w = 4)).unwrap();
+
+    assert_ne!(old_id, new.id());
+    assert_eq!(1, counter.0.load(Ordering::SeqCst));
+    assert_eq!(4, block_on_all(new.read().map(|v| **v)).unwrap());
+}
+
+#[test]
+fn recycled_lock_has_no_tag_yet() {
+    let pool = RwLockPool::new(1);
+    let old = pool.get(Tagged::new(1));
+
+    block_on_all(old.write().map(|mut w| **w = 2)).unwrap();
+    pool.recycle(old);
+
+    assert_eq!(None, pool.get(Tagged::new(3)).last_tag());
+}
+
+#[test]
+fn recycling_drops_the_value() {
+    let pool = RwLockPool::new(1);
+    let value = Arc::new(1);
+
+    pool.recycle(pool.get(Tagged::new(Some(value.clone()))));
+    assert_eq!(1, Arc::strong_count(&value));
+}
+
+#[test]
+fn streams_of_the_previous_owner_end() {
+    let pool = RwLockPool::new(1);
+    let old = pool.get(Tagged::new(1));
+    let stream = old.subscribe();
+
+    pool.recycle(old);
+
+    let new = pool.get(Tagged::new(2));
+    block_on_all(new.write().map(|mut w| **w = 3)).unwrap();
+
+    assert_eq!(
+        Vec::<VersionTag>::new(),
+        block_on_all(stream.collect()).unwrap()
+    );
+}
@@ -0,0 +1,28 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn the_guards_are_released_with_the_scope() {
+    let lock = RwLock::new_tagged(1);
+    let value = lock
+        .scope(|scope| Box::new(scope.read().map(|r| **r)))
+        .wait()
+        .unwrap();
+
+    assert_eq!(1, value);
+    assert!(lock.write().wait().is_ok());
+}
+
+#[test]
+fn writes_in_the_scope_commit() {
+    let lock = RwLock::new_tagged(1);
+
+    lock.scope(|scope| Box::new(scope.write().map(|mut w| **w = 2)))
+        .wait()
+        .unwrap();
+
+    let value = lock.read().wait().unwrap();
+
+    assert_eq!(2, **value);
+    assert_eq!(lock.last_tag(), Some(value.tag()));
+}
@@ -0,0 +1,24 @@
+use futures::Future;
+use futures_tag_locks::{RwLock, Tagged};
+
+#[test]
+fn result_tags_the_ok_value() {
+    let lock: RwLock<Result<Tagged<usize>, &str>> = RwLock::new(Err("not loaded"));
+
+    let failed = lock.write().map(|w| w.new_tag()).wait().unwrap();
+    assert_eq!(Some(failed), lock.last_tag());
+
+    let tag = lock
+        .write()
+        .map(|mut w| {
+            *w = Ok(Tagged::new(10));
+            w.new_tag()
+        })
+        .wait()
+        .unwrap();
+
+    let value = lock.read().wait().unwrap();
+
+    assert_ne!(failed, tag);
+    assert_eq!(Some(tag), value.as_ref().ok().map(Tagged::tag));
+}
@@ -0,0 +1,38 @@
+use futures::Future;
+use futures_tag_locks::Singleflight;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::executor::current_thread::block_on_all;
+
+#[test]
+fn concurrent_calls_run_one_computation() {
+    let flights = Singleflight::new();
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let call = |value| {
+        let runs = runs.clone();
+
+        flights.call("user", move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ()>(value)
+        })
+    };
+
+    let (a, b) = block_on_all(call(10).join(call(12))).unwrap();
+
+    assert_eq!(1, runs.load(Ordering::SeqCst));
+    assert_eq!(10, *b);
+    assert_eq!(a.tag(), b.tag());
+}
+
+#[test]
+fn next_call_after_landing_runs_again() {
+    let flights = Singleflight::new();
+
+    let a = block_on_all(flights.call(1, || Ok::<_, ()>("a"))).unwrap();
+    assert!(!flights.is_in_flight(&1));
+
+    let b = block_on_all(flights.call(1, || Ok::<_, ()>("b"))).unwrap();
+    assert_eq!("b", *b);
+    assert_ne!(a.tag(), b.tag());
+}
@@ -0,0 +1,21 @@
+use futures::Future;
+use futures_tag_locks::{StaticTagLock, Tagged};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CACHE: StaticTagLock<usize> = StaticTagLock::new();
+
+#[test]
+fn initializes_once_on_first_use() {
+    let calls = AtomicUsize::new(0);
+    let init = || {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Ok::<_, ()>(Tagged::new(1))
+    };
+
+    let first = CACHE.get_or_init(init).map(|v| v.tag()).wait().unwrap();
+    let second = CACHE.get_or_init(init).map(|v| v.tag()).wait().unwrap();
+
+    assert_eq!(1, calls.load(Ordering::SeqCst));
+    assert_eq!(first, second);
+    assert!(CACHE.lock().last_tag().is_some());
+}
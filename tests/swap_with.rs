@@ -0,0 +1,38 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn swaps_the_values_and_bumps_both_tags() {
+    let blue = RwLock::new_tagged("v1");
+    let green = RwLock::new_tagged("v2");
+    let (b, g) = (blue.last_tag(), green.last_tag());
+
+    blue.swap_with(&green).wait().unwrap();
+
+    assert_eq!("v2", **blue.read().wait().unwrap());
+    assert_eq!("v1", **green.read().wait().unwrap());
+    assert!(blue.last_tag().is_some() && blue.last_tag() != b);
+    assert!(green.last_tag().is_some() && green.last_tag() != g);
+}
+
+#[test]
+fn swapping_in_both_orders_restores_the_values() {
+    let blue = RwLock::new_tagged(1);
+    let green = RwLock::new_tagged(2);
+
+    blue.swap_with(&green).wait().unwrap();
+    green.swap_with(&blue).wait().unwrap();
+
+    assert_eq!(1, **blue.read().wait().unwrap());
+    assert_eq!(2, **green.read().wait().unwrap());
+}
+
+#[test]
+fn swapping_with_itself_is_a_no_op() {
+    let lock = RwLock::new_tagged(1);
+    let tag = lock.read().wait().unwrap().tag();
+
+    lock.swap_with(&lock.clone()).wait().unwrap();
+
+    assert_eq!(tag, lock.read().wait().unwrap().tag());
+}
@@ -0,0 +1,45 @@
+use futures::Future;
+use futures_tag_locks::{sync, RwLock};
+
+#[test]
+fn copies_the_value_and_the_tag_when_stale() {
+    let primary = RwLock::new_tagged(1);
+    let replica = RwLock::new_tagged(0);
+
+    assert!(sync(&primary, &replica).wait().unwrap());
+
+    let (from, to) = (
+        primary.read().wait().unwrap(),
+        replica.read().wait().unwrap(),
+    );
+
+    assert_eq!(1, **to);
+    assert_eq!(from.tag(), to.tag());
+}
+
+#[test]
+fn does_nothing_when_up_to_date() {
+    let primary = RwLock::new_tagged(1);
+    let replica = RwLock::new_tagged(0);
+
+    sync(&primary, &replica).wait().unwrap();
+
+    assert!(!sync(&primary, &replica).wait().unwrap());
+    assert!(!sync(&primary, &primary).wait().unwrap());
+}
+
+#[test]
+fn syncs_in_both_directions() {
+    let a = RwLock::new_tagged(1);
+    let b = RwLock::new_tagged(2);
+
+    sync(&a, &b).wait().unwrap();
+    **b.write().wait().unwrap() = 3;
+    sync(&b, &a).wait().unwrap();
+
+    assert_eq!(3, **a.read().wait().unwrap());
+    assert_eq!(
+        b.read().wait().unwrap().tag(),
+        a.read().wait().unwrap().tag()
+    );
+}
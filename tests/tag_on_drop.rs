@@ -0,0 +1,28 @@
+use futures_tag_locks::{TagOnDrop, Tagged};
+use std::sync::RwLock;
+
+#[test]
+fn tags_the_foreign_guard_on_drop() {
+    let lock = RwLock::new(Tagged::new(0));
+    let before = lock.read().unwrap().tag();
+
+    let tag = {
+        let mut guard = TagOnDrop::new(lock.write().unwrap());
+        **guard = 1;
+        assert_eq!(before, guard.tag());
+        guard.new_tag()
+    };
+
+    let value = lock.read().unwrap();
+    assert_eq!((1, tag), (**value, value.tag()));
+}
+
+#[test]
+fn each_adapter_applies_a_new_tag() {
+    let lock = RwLock::new(Tagged::new(0));
+    let first = TagOnDrop::new(lock.write().unwrap()).new_tag();
+    let second = TagOnDrop::new(lock.write().unwrap()).new_tag();
+
+    assert_ne!(first, second);
+    assert_eq!(second, lock.read().unwrap().tag());
+}
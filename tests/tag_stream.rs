@@ -0,0 +1,58 @@
+#![cfg(feature = "test-util")]
+
+use futures::{Async, Future, Stream};
+use futures_tag_locks::test_util::Harness;
+use futures_tag_locks::{RwLock, Tagged};
+use version_tag::VersionTag;
+
+fn write(lock: &RwLock<Tagged<usize>>, value: usize) -> VersionTag {
+    lock.write()
+        .map(|mut w| {
+            **w = value;
+            w.new_tag()
+        })
+        .wait()
+        .unwrap()
+}
+
+#[test]
+fn slow_consumer_only_sees_the_latest_tag() {
+    let lock = RwLock::new_tagged(0);
+    let stream = lock.subscribe();
+
+    write(&lock, 1);
+    write(&lock, 2);
+    let last = write(&lock, 3);
+
+    let mut next = Harness::new(stream.into_future());
+
+    let stream = match next.poll() {
+        Ok(Async::Ready((tag, stream))) => {
+            assert_eq!(Some(last), tag);
+            stream
+        }
+        _ => panic!("the stream should yield the latest tag"),
+    };
+
+    let mut next = Harness::new(stream.into_future());
+    assert!(matches!(next.poll(), Ok(Async::NotReady)));
+
+    let tag = write(&lock, 4);
+    assert!(next.is_notified());
+
+    match next.poll() {
+        Ok(Async::Ready((next, _))) => assert_eq!(Some(tag), next),
+        _ => panic!("the stream should yield the new tag"),
+    }
+}
+
+#[test]
+fn ends_when_the_lock_is_dropped() {
+    let lock = RwLock::new_tagged(0);
+    let stream = lock.subscribe();
+
+    drop(lock);
+
+    let (tag, _) = stream.into_future().wait().ok().unwrap();
+    assert_eq!(None, tag);
+}
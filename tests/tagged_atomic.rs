@@ -0,0 +1,18 @@
+use futures_tag_locks::TaggedAtomic;
+use std::panic::{self, AssertUnwindSafe};
+
+#[test]
+fn panicking_update_does_not_poison_the_value() {
+    let atomic = TaggedAtomic::new(1);
+    let (_, before) = atomic.load();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        atomic.update(|_| panic!("update failed"));
+    }));
+
+    assert!(result.is_err());
+    assert_eq!((1, before), atomic.load());
+
+    let tag = atomic.store(2);
+    assert_eq!((2, tag), atomic.load());
+}
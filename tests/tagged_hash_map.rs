@@ -0,0 +1,61 @@
+use futures::Future;
+use futures_tag_locks::{RwLock, SetTag, TaggedHashMap, MAX_REMOVED_KEYS};
+use version_tag::VersionTag;
+
+#[test]
+fn discarded_modify_does_not_mark_the_touched_keys() {
+    let lock = RwLock::new(TaggedHashMap::new());
+
+    lock.write()
+        .map(|mut w| {
+            w.insert("a", 1);
+            w.insert("b", 2);
+        })
+        .wait()
+        .unwrap();
+
+    let seen = lock.read().map(|m| m.tag()).wait().unwrap();
+
+    lock.modify(|m| m.get_mut("a").is_none()).wait().unwrap();
+    lock.write().map(|mut w| w.remove("b")).wait().unwrap();
+
+    lock.read()
+        .map(move |m| assert_eq!(vec![&"b"], m.changed_keys(seen)))
+        .wait()
+        .unwrap();
+}
+
+#[test]
+fn removed_keys_are_bounded() {
+    let count = 2 * MAX_REMOVED_KEYS;
+    let mut map = TaggedHashMap::new();
+
+    (0..count).for_each(|k| {
+        map.insert(k, ());
+    });
+    map.set_tag(VersionTag::new());
+
+    let mut recent = None;
+
+    for k in 0..count {
+        map.remove(&k);
+
+        if k == count - 10 {
+            map.insert(count, ());
+            recent = Some(VersionTag::new());
+            map.set_tag(recent.unwrap());
+        } else {
+            map.set_tag(VersionTag::new());
+        }
+    }
+
+    let mut changed: Vec<usize> = map
+        .changed_keys(recent.unwrap())
+        .into_iter()
+        .copied()
+        .collect();
+    changed.sort();
+    assert_eq!((count - 9..count).collect::<Vec<_>>(), changed);
+
+    assert!(map.changed_keys(VersionTag::new()).len() <= MAX_REMOVED_KEYS + 1);
+}
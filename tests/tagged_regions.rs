@@ -0,0 +1,61 @@
+use futures::Future;
+use futures_tag_locks::{RwLock, TaggedRegions};
+
+fn lock() -> RwLock<TaggedRegions<Vec<u8>>> {
+    RwLock::new(TaggedRegions::new(vec![0; 40], 10))
+}
+
+#[test]
+fn a_range_across_regions_tags_each_of_them() {
+    let lock = lock();
+    let before = lock.read().wait().unwrap().region_tag(0);
+
+    let tag = lock
+        .write()
+        .map(|mut w| {
+            w.mark_region(15..25);
+            w.new_tag()
+        })
+        .wait()
+        .unwrap();
+
+    let regions = lock.read().wait().unwrap();
+
+    assert_eq!(before, regions.region_tag(0));
+    assert_eq!(tag, regions.region_tag(1));
+    assert_eq!(tag, regions.region_tag(2));
+    assert_eq!(before, regions.region_tag(3));
+    assert_eq!(tag, regions.region_tag_at(24));
+    assert_eq!(tag, regions.tag());
+}
+
+#[test]
+fn unmarked_writes_only_tag_the_whole_value() {
+    let lock = lock();
+    let before = lock.read().wait().unwrap().region_tag(1);
+    let tag = lock.write().wait().unwrap().finish();
+    let regions = lock.read().wait().unwrap();
+
+    assert_eq!(before, regions.region_tag(1));
+    assert_eq!(tag, regions.tag());
+}
+
+#[test]
+fn mark_all_tags_every_region() {
+    let lock = lock();
+
+    lock.write().wait().unwrap().mark_region(0..5);
+
+    let tag = lock
+        .write()
+        .map(|mut w| {
+            w.mark_all();
+            w.new_tag()
+        })
+        .wait()
+        .unwrap();
+
+    let regions = lock.read().wait().unwrap();
+
+    assert!((0..4).all(|r| regions.region_tag(r) == tag));
+}
@@ -0,0 +1,44 @@
+use futures::Future;
+use futures_tag_locks::TaggedSeq;
+use std::thread;
+
+#[test]
+fn writes_return_the_tag_loaded_with_the_value() {
+    let seq = TaggedSeq::new(1);
+    let before = seq.tag();
+    let tag = seq.write(|v| *v += 1).wait().unwrap();
+
+    assert_ne!(before, tag);
+    assert_eq!((2, tag), seq.load());
+}
+
+#[test]
+fn concurrent_writes_are_serialized() {
+    let seq = TaggedSeq::new(0usize);
+
+    let writers: Vec<_> = (0..4)
+        .map(|_| {
+            let seq = seq.clone();
+
+            thread::spawn(move || {
+                (0..100)
+                    .map(|_| seq.write(|v| *v += 1).wait().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    let reader = {
+        let seq = seq.clone();
+        thread::spawn(move || (0..1000).all(|_| seq.get() <= 400))
+    };
+
+    let tags: Vec<_> = writers
+        .into_iter()
+        .flat_map(|w| w.join().unwrap())
+        .collect();
+
+    assert!(reader.join().unwrap());
+    assert_eq!(400, seq.get());
+    assert!(tags.contains(&seq.tag()));
+}
@@ -0,0 +1,20 @@
+use futures::{stream, Future, Sink, Stream};
+use futures_tag_locks::RwLock;
+
+#[test]
+fn each_item_is_a_write() {
+    let lock = RwLock::new_tagged(0);
+    let tags = lock.subscribe();
+
+    lock.sink()
+        .send_all(stream::iter_ok(vec![1, 2, 3]))
+        .map(drop)
+        .wait()
+        .unwrap();
+
+    let value = lock.read().map(|v| (**v, v.tag())).wait().unwrap();
+    let (tag, _) = tags.into_future().wait().ok().unwrap();
+
+    assert_eq!(3, value.0);
+    assert_eq!(Some(value.1), tag);
+}
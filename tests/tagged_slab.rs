@@ -0,0 +1,39 @@
+use futures::Future;
+use futures_tag_locks::{RwLock, TaggedSlab};
+
+#[test]
+fn discarded_modify_does_not_tag_the_touched_slots() {
+    let lock = RwLock::new(TaggedSlab::new());
+    let (a, b) = lock
+        .write()
+        .map(|mut w| (w.insert(1), w.insert(2)))
+        .wait()
+        .unwrap();
+    let before = lock.read().map(move |s| s.tag_of(a)).wait().unwrap();
+
+    lock.modify(move |s| s.get_mut(a).is_none()).wait().unwrap();
+    lock.write().map(move |mut w| w.remove(b)).wait().unwrap();
+
+    lock.read()
+        .map(move |s| {
+            assert_eq!(before, s.tag_of(a));
+            assert_eq!(None, s.tag_of(b));
+        })
+        .wait()
+        .unwrap();
+}
+
+#[test]
+fn remove_commits_a_new_tag() {
+    let lock = RwLock::new(TaggedSlab::new());
+    let key = lock.write().map(|mut w| w.insert(1)).wait().unwrap();
+    let before = lock.read().map(|s| s.tag()).wait().unwrap();
+
+    let removed = lock
+        .modify(move |s| s.remove(key).is_some())
+        .wait()
+        .unwrap();
+
+    assert_ne!(Some(before), removed);
+    assert_eq!(removed, lock.read().map(|s| s.tag()).wait().ok());
+}
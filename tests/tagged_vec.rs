@@ -0,0 +1,33 @@
+use futures::Future;
+use futures_tag_locks::{RwLock, TaggedVec};
+
+#[test]
+fn discarded_modify_does_not_tag_the_touched_elements() {
+    let lock = RwLock::new(TaggedVec::from(vec![1, 2, 3]));
+    let before = lock.read().map(|v| v.tag_at(0)).wait().unwrap();
+
+    let committed = lock
+        .modify(|v| {
+            v.get_mut(0);
+            false
+        })
+        .wait()
+        .unwrap();
+
+    assert_eq!(None, committed);
+
+    lock.modify(|v| {
+        v.set(2, 4);
+        true
+    })
+    .wait()
+    .unwrap();
+
+    lock.read()
+        .map(move |v| {
+            assert_eq!(before, v.tag_at(0));
+            assert_eq!(Some(v.tag()), v.tag_at(2));
+        })
+        .wait()
+        .unwrap();
+}
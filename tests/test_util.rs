@@ -0,0 +1,35 @@
+#![cfg(feature = "test-util")]
+
+use futures::Future;
+use futures_tag_locks::test_util::{
+    assert_tag_changed, assert_tag_unchanged, MockClock, TagSequence,
+};
+use futures_tag_locks::{RwLock, RwLockBuilder, Tagged};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn coalescing() -> RwLock<Tagged<usize>> {
+    RwLockBuilder::new()
+        .clock(Arc::new(MockClock::new()))
+        .coalesce(Duration::from_secs(1))
+        .build(Tagged::new(0))
+}
+
+#[test]
+fn commits_are_seen_before_the_coalesced_notification() {
+    let lock = coalescing();
+
+    assert_tag_changed(&lock, lock.write().map(|mut w| **w = 1)).unwrap();
+    assert_tag_unchanged(&lock, lock.read().map(drop)).unwrap();
+}
+
+#[test]
+fn sequences_number_the_coalesced_writes() {
+    let lock = coalescing();
+    let sequence = TagSequence::new(&lock);
+    let first = lock.write().wait().unwrap().finish();
+    let second = lock.write().wait().unwrap().finish();
+
+    assert_eq!(vec![first, second], sequence.tags());
+    assert_eq!(Some(2), sequence.ordinal(second));
+}
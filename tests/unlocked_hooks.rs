@@ -0,0 +1,36 @@
+#![cfg(feature = "test-util")]
+
+use futures::{Async, Future};
+use futures_tag_locks::test_util::Harness;
+use futures_tag_locks::RwLock;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn unlocked_hook_runs_when_a_writer_is_waiting() {
+    let lock = RwLock::new_tagged(0);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    {
+        let seen = seen.clone();
+        lock.on_write_unlocked(move |v, tag| seen.lock().unwrap().push((**v, tag)));
+    }
+
+    let mut first = lock.write().wait().unwrap();
+    let mut second = Harness::new(lock.write());
+
+    assert!(matches!(second.poll(), Ok(Async::NotReady)));
+
+    **first = 1;
+    let tag = first.new_tag();
+    drop(first);
+
+    assert_eq!(vec![(1, tag)], *seen.lock().unwrap());
+
+    match second.poll() {
+        Ok(Async::Ready(mut w)) => **w = 2,
+        _ => panic!("the waiting writer should have the lock"),
+    }
+
+    assert_eq!(2, seen.lock().unwrap().len());
+    assert_eq!(2, seen.lock().unwrap()[1].0);
+}
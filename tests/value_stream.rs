@@ -0,0 +1,27 @@
+use futures::{Future, Stream};
+use futures_tag_locks::RwLock;
+
+#[test]
+fn yields_every_write() {
+    let lock = RwLock::new_tagged(0);
+    let values = lock.value_stream();
+
+    let tags: Vec<_> = (1..=3)
+        .map(|v| {
+            lock.write()
+                .map(move |mut w| {
+                    **w = v;
+                    w.new_tag()
+                })
+                .wait()
+                .unwrap()
+        })
+        .collect();
+
+    drop(lock);
+
+    let values: Vec<_> = values.map(|(tag, v)| (tag, *v)).collect().wait().unwrap();
+    let expected: Vec<_> = tags.into_iter().zip(1..=3).collect();
+
+    assert_eq!(expected, values);
+}
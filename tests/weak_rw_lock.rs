@@ -0,0 +1,27 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn upgrade_shares_the_value() {
+    let lock = RwLock::new_tagged(1);
+    let weak = lock.downgrade();
+
+    let upgraded = weak.upgrade().unwrap();
+    upgraded.write().map(|mut w| **w = 2).wait().unwrap();
+
+    assert_eq!(lock.id(), upgraded.id());
+    assert_eq!(2, lock.read().map(|r| **r).wait().unwrap());
+}
+
+#[test]
+fn upgrade_fails_once_the_locks_are_dropped() {
+    let lock = RwLock::new_tagged(1);
+    let weak = lock.downgrade();
+    let other = lock.clone();
+
+    drop(lock);
+    assert!(weak.upgrade().is_some());
+
+    drop(other);
+    assert!(weak.upgrade().is_none());
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "test-util")]
+
+use futures::{Async, Future, Stream};
+use futures_tag_locks::test_util::Harness;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn reports_what_the_commit_notified() {
+    let lock = RwLock::new_tagged(0);
+    let _values = lock.value_stream();
+    let mut stream = Harness::new(lock.subscribe().into_future());
+
+    lock.on_write(|_, _| {});
+    assert!(matches!(stream.poll(), Ok(Async::NotReady)));
+
+    let notified = lock.write_and_notify(|v| **v = 1).wait().unwrap();
+
+    assert_eq!(2, notified.hooks);
+    assert_eq!(0, notified.observers);
+    assert_eq!(1, notified.streams);
+    assert_eq!(Some(notified.tag), lock.last_tag());
+}
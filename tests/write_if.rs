@@ -0,0 +1,24 @@
+use futures::Future;
+use futures_tag_locks::RwLock;
+
+#[test]
+fn a_failed_predicate_releases_without_bumping_the_tag() {
+    let lock = RwLock::new_tagged(1);
+    let before = lock.read().wait().unwrap().tag();
+
+    assert!(lock.write_if(|v| **v > 1).wait().unwrap().is_none());
+    assert_eq!(before, lock.read().wait().unwrap().tag());
+    assert!(lock.write().wait().is_ok());
+}
+
+#[test]
+fn a_passing_predicate_hands_out_the_guard() {
+    let lock = RwLock::new_tagged(1);
+    let mut guard = lock.write_if(|v| **v == 1).wait().unwrap().unwrap();
+
+    **guard = 2;
+    let tag = guard.finish();
+
+    assert_eq!(Some(tag), lock.last_tag());
+    assert_eq!(2, **lock.read().wait().unwrap());
+}